@@ -0,0 +1,109 @@
+use crate::{MatchingPipeline, PipelineError, PipelineResult, TerminatedPipeline};
+
+/// Encoding of the length in front of a [length-prefixed](MatchingPipeline::expect_length_prefixed) payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthPrefix{
+    U8,
+    U16Be,
+    U16Le,
+    U32Be,
+    U32Le
+}
+
+impl LengthPrefix{
+    /// Size of the prefix in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::U16Be | Self::U16Le => 2,
+            Self::U32Be | Self::U32Le => 4
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> usize {
+        match self {
+            Self::U8 => bytes[0] as usize,
+            Self::U16Be => u16::from_be_bytes([bytes[0], bytes[1]]) as usize,
+            Self::U16Le => u16::from_le_bytes([bytes[0], bytes[1]]) as usize,
+            Self::U32Be => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize,
+            Self::U32Le => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize
+        }
+    }
+}
+
+impl<'i, 'a> MatchingPipeline<'i, u8>{
+    /// Expects that `n` bytes can be matched
+    /// 
+    /// * `n` - The number of bytes
+    pub fn expect_bytes(mut self, n: usize) -> PipelineResult<'i, 'a, u8> {
+        if self.input.len() - self.pos < n {
+            return Err(PipelineError::UnexpectedEos { position: self.position() });
+        }
+
+        self.advance(n, n);
+        Ok(self)
+    }
+
+    /// Expects `N` bytes and returns them
+    fn expect_array<const N: usize>(self) -> Result<(Self, [u8; N]), PipelineError<'a, u8>> {
+        let pipeline = self.expect_bytes(N)?;
+        let bytes = pipeline.input.slice(pipeline.pos - N..pipeline.pos);
+
+        Ok((pipeline, bytes.as_ref().try_into().expect("N bytes were matched")))
+    }
+
+    /// Expects a byte and returns it
+    pub fn expect_u8(self) -> Result<(Self, u8), PipelineError<'a, u8>> {
+        self.expect_array().map(|(p, bytes)| (p, u8::from_be_bytes(bytes)))
+    }
+
+    /// Expects a big endian u16 and returns it
+    pub fn expect_u16_be(self) -> Result<(Self, u16), PipelineError<'a, u8>> {
+        self.expect_array().map(|(p, bytes)| (p, u16::from_be_bytes(bytes)))
+    }
+
+    /// Expects a little endian u16 and returns it
+    pub fn expect_u16_le(self) -> Result<(Self, u16), PipelineError<'a, u8>> {
+        self.expect_array().map(|(p, bytes)| (p, u16::from_le_bytes(bytes)))
+    }
+
+    /// Expects a big endian u32 and returns it
+    pub fn expect_u32_be(self) -> Result<(Self, u32), PipelineError<'a, u8>> {
+        self.expect_array().map(|(p, bytes)| (p, u32::from_be_bytes(bytes)))
+    }
+
+    /// Expects a little endian u32 and returns it
+    pub fn expect_u32_le(self) -> Result<(Self, u32), PipelineError<'a, u8>> {
+        self.expect_array().map(|(p, bytes)| (p, u32::from_le_bytes(bytes)))
+    }
+
+    /// Expects a big endian u64 and returns it
+    pub fn expect_u64_be(self) -> Result<(Self, u64), PipelineError<'a, u8>> {
+        self.expect_array().map(|(p, bytes)| (p, u64::from_be_bytes(bytes)))
+    }
+
+    /// Expects a little endian u64 and returns it
+    pub fn expect_u64_le(self) -> Result<(Self, u64), PipelineError<'a, u8>> {
+        self.expect_array().map(|(p, bytes)| (p, u64::from_le_bytes(bytes)))
+    }
+
+    /// Expects a length followed by as many bytes
+    /// 
+    /// Both are added to the matched symbols.
+    /// Returns the pipeline along with the payload as a [TerminatedPipeline].
+    /// 
+    /// * `prefix` - How the length is encoded
+    pub fn expect_length_prefixed(self, prefix: LengthPrefix) -> Result<(Self, TerminatedPipeline<'i, u8>), PipelineError<'a, u8>> {
+        let pipeline = self.expect_bytes(prefix.size())?;
+        let length = prefix.decode(&pipeline.input.slice(pipeline.pos - prefix.size()..pipeline.pos));
+
+        let mut payload = pipeline.clone();
+        payload.segments = Default::default();
+        payload.start = payload.pos;
+
+        let payload = payload.expect_bytes(length)?;
+        let pipeline = pipeline.expect_bytes(length)?;
+
+        Ok((pipeline, payload.terminate()))
+    }
+}
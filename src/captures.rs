@@ -0,0 +1,92 @@
+use std::{borrow::Cow, ops::Range, rc::Rc};
+
+use crate::{source::Source, MatchingPipeline, PipelineResult, Symbol, TerminatedPipeline};
+
+/// A capture as recorded by a pipeline
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Record{
+    name: Rc<str>,
+    /// Span in symbols
+    span: Range<usize>,
+    /// Span in units of the source
+    units: Range<usize>
+}
+
+/// A named part of the input, see [capture](MatchingPipeline::capture)
+#[derive(Debug, Clone)]
+pub struct Capture<'i, S:Symbol>{
+    record: Record,
+    input: Source<'i, S>
+}
+
+impl<'i, S:Symbol> Capture<'i, S>{
+    pub fn name(&self) -> &str {
+        &self.record.name
+    }
+
+    /// Offsets of the first symbol captured and of the symbol right after the capture
+    pub fn span(&self) -> Range<usize> {
+        self.record.span.clone()
+    }
+
+    /// The captured symbols, skipped ones included
+    pub fn symbols(&self) -> Cow<'i, [S]> {
+        self.input.slice(self.record.units.clone())
+    }
+}
+
+impl<'i> Capture<'i, char>{
+    /// The captured text, borrowed from the input when it is text
+    pub fn as_str(&self) -> Cow<'i, str> {
+        match self.input.text(self.record.units.clone()) {
+            Some(text) => Cow::Borrowed(text),
+            None => Cow::Owned(self.symbols().iter().collect())
+        }
+    }
+}
+
+impl<'i, 'a, S:Symbol+'a> MatchingPipeline<'i, S>{
+    /// Records the part of the input matched by `callback` under `name`
+    /// 
+    /// A name can be captured several times, by a quantifier for instance
+    /// 
+    /// * `name` - The name of the capture
+    pub fn capture<F, E>(self, name: impl Into<Rc<str>>, callback: F) -> PipelineResult<'i, 'a, S, E>
+    where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>
+    {
+        let start = self.clone();
+        let pipeline = callback(self)?;
+
+        Ok(pipeline.record_capture(name.into(), &start))
+    }
+
+    /// Records what was matched since `start` under `name`
+    pub(crate) fn record_capture(mut self, name: Rc<str>, start: &Self) -> Self {
+        self.captures.push(Record {
+            name,
+            span: start.offset..self.offset,
+            units: start.pos..self.pos
+        });
+
+        self
+    }
+}
+
+impl<'i, S:Symbol> TerminatedPipeline<'i, S>{
+    /// Every capture, in the order they ended
+    pub fn captures(&self) -> Vec<Capture<'i, S>> {
+        self.pipeline.captures.items().into_iter()
+            .map(|record| Capture { record, input: self.pipeline.input.clone() })
+            .collect()
+    }
+
+    /// The last capture named `name`
+    pub fn get(&self, name: &str) -> Option<Capture<'i, S>> {
+        self.captures().into_iter().rev().find(|capture| capture.name() == name)
+    }
+
+    /// Every capture named `name`, in the order they ended
+    pub fn get_all(&self, name: &str) -> Vec<Capture<'i, S>> {
+        self.captures().into_iter().filter(|capture| capture.name() == name).collect()
+    }
+}
@@ -0,0 +1,83 @@
+//! Ready-made classes of [char]
+//! 
+//! A class can be used as a predicate, with [predicate](CharClass::predicate),
+//! or matched with [expect_class](MatchingPipeline::expect_class) whose errors name it.
+
+use std::borrow::Cow;
+
+use crate::{MatchingPipeline, PipelineError, PipelineResult};
+
+/// A named set of [char]
+#[derive(Debug, Clone, Copy)]
+pub struct CharClass{
+    name: &'static str,
+    predicate: fn(&char) -> bool
+}
+
+impl CharClass{
+    /// Creates a class named `name`, holding the chars matching `predicate`
+    pub const fn new(name: &'static str, predicate: fn(&char) -> bool) -> Self {
+        Self { name, predicate }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Tells if `c` belongs to the class
+    pub fn contains(&self, c: &char) -> bool {
+        (self.predicate)(c)
+    }
+
+    /// The predicate of the class, for [expect_predicate](MatchingPipeline::expect_predicate)
+    /// or [match_while_true](MatchingPipeline::match_while_true)
+    pub fn predicate(&self) -> fn(&char) -> bool {
+        self.predicate
+    }
+}
+
+/// `0` to `9`
+pub const ASCII_DIGIT: CharClass = CharClass::new("digit", char::is_ascii_digit);
+
+/// `0` to `9`, `a` to `f` and `A` to `F`
+pub const HEX_DIGIT: CharClass = CharClass::new("hex digit", char::is_ascii_hexdigit);
+
+/// `a` to `z` and `A` to `Z`
+pub const ASCII_ALPHA: CharClass = CharClass::new("ASCII letter", char::is_ascii_alphabetic);
+
+/// [ASCII_ALPHA] and [ASCII_DIGIT]
+pub const ASCII_ALNUM: CharClass = CharClass::new("ASCII letter or digit", char::is_ascii_alphanumeric);
+
+/// ASCII punctuation, like `!` or `{`
+pub const ASCII_PUNCTUATION: CharClass = CharClass::new("punctuation", char::is_ascii_punctuation);
+
+/// Unicode whitespace, line breaks included
+pub const WHITESPACE: CharClass = CharClass::new("whitespace", |c| c.is_whitespace());
+
+/// Any Unicode letter
+pub const LETTER: CharClass = CharClass::new("letter", |c| c.is_alphabetic());
+
+/// Any Unicode number, like `7` or `Ⅶ`
+pub const NUMBER: CharClass = CharClass::new("number", |c| c.is_numeric());
+
+/// Any Unicode letter or number, and `_`
+pub const WORD: CharClass = CharClass::new("word character", |c| c.is_alphanumeric() || *c == '_');
+
+/// Any Unicode letter or number
+pub const ALPHANUMERIC: CharClass = CharClass::new("letter or number", |c| c.is_alphanumeric());
+
+impl<'i, 'a> MatchingPipeline<'i, char>{
+    /// Expects that the current char belongs to `class`
+    pub fn expect_class(mut self, class: CharClass) -> PipelineResult<'i, 'a, char> {
+        let Some((actual, width)) = self.current() else {
+            return Err(PipelineError::UnexpectedEos { position: self.position() });
+        };
+
+        if !class.contains(&actual) {
+            return Err(PipelineError::SymbolNotInClass { class: Cow::Borrowed(class.name), actual: *actual, position: self.position() });
+        }
+
+        self.advance(width, 1);
+        Ok(self)
+    }
+}
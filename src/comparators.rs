@@ -0,0 +1,48 @@
+//! Built-in [Comparator](crate::Comparator)s for [char]
+//! 
+//! Symbols are compared one at a time, so equivalences between sequences
+//! of different lengths, like `ß` and `ss`, `ﬁ` and `fi` or `é` and `e` followed by
+//! a combining accent, are not recognized. To match text with combining marks,
+//! normalize it with [begin_match_nfc](crate::begin_match_nfc) or [begin_match_nfkc](crate::begin_match_nfkc).
+//! For full case folding, use [begin_match_case_folded](crate::begin_match_case_folded) along with [fold_case].
+
+use std::iter::once;
+
+use caseless::Caseless;
+use unicode_normalization::UnicodeNormalization;
+
+/// Ignores the case of ASCII letters
+pub fn ascii_case_insensitive(expected: &char, actual: &char) -> bool {
+    expected.eq_ignore_ascii_case(actual)
+}
+
+/// Ignores the case of letters with simple, single char, case folding, like `Σ`, `σ` and `ς`
+/// 
+/// This is not full Unicode case folding: letters whose folding takes several chars
+/// only match themselves, so `ß` does not match `SS`, see [fold_case] instead
+pub fn simple_case_insensitive(expected: &char, actual: &char) -> bool {
+    expected == actual
+    || expected.to_lowercase().eq(actual.to_lowercase())
+    || expected.to_uppercase().eq(actual.to_uppercase())
+}
+
+/// Compares the canonical compositions (NFC) of both chars, like `Å` (U+212B) and `Å` (U+00C5)
+/// 
+/// Only chars that are equivalent on their own are recognized,
+/// a char followed by combining marks is not composed with them
+pub fn nfc(expected: &char, actual: &char) -> bool {
+    expected == actual || once(*expected).nfc().eq(once(*actual).nfc())
+}
+
+/// Compares the compatibility compositions (NFKC) of both chars, like `²` and `2`
+/// 
+/// Only chars that are equivalent on their own are recognized, as for [nfc]
+pub fn nfkc(expected: &char, actual: &char) -> bool {
+    expected == actual || once(*expected).nfkc().eq(once(*actual).nfkc())
+}
+
+/// Applies full Unicode case folding to `text`, for patterns matched
+/// against a [case folded pipeline](crate::begin_match_case_folded)
+pub fn fold_case(text: &str) -> Vec<char> {
+    text.chars().default_case_fold().collect()
+}
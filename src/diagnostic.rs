@@ -0,0 +1,181 @@
+use std::fmt::Display;
+
+use crate::{PipelineError, RegexError};
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders a [PipelineError] along with the line of the input it occurred on
+/// 
+/// ```text
+/// error: Expected ':' but instead got '='
+///  --> line 2, column 5
+///   |
+/// 2 | port= 80
+///   |     ^ expected ':'
+///   |
+///   = note: keys and values are separated by ':'
+/// ```
+#[derive(Debug, Clone)]
+pub struct Diagnostic<'s>{
+    source: &'s str,
+    message: String,
+    expected: Vec<String>,
+    /// Offset of the failing span, in chars
+    offset: usize,
+    /// Length of the failing span, in chars
+    length: usize,
+    notes: Vec<String>,
+    colored: bool
+}
+
+impl<'s> Diagnostic<'s>{
+    /// Creates the diagnostic of an `error` raised while matching `source`
+    pub fn new(source: &'s str, error: &PipelineError<'_, char>) -> Self {
+        Self {
+            source,
+            message: error.message(),
+            expected: expected(error),
+            offset: error.position().offset,
+            length: span_length(error),
+            notes: vec![],
+            colored: false
+        }
+    }
+
+    /// Creates the diagnostic of a syntax error in a regular expression `pattern`
+    pub fn for_regex(pattern: &'s str, error: &RegexError) -> Self {
+        Self {
+            source: pattern,
+            message: error.kind.to_string(),
+            expected: vec![],
+            offset: error.span.start,
+            length: error.span.len(),
+            notes: vec![],
+            colored: false
+        }
+    }
+
+    /// Adds a note at the end of the diagnostic
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Uses ANSI escape codes to color the diagnostic
+    pub fn colored(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+
+    /// Returns the escape code `code`, or nothing if the diagnostic is plain
+    fn style(&self, code: &'static str) -> &'static str {
+        if self.colored { code } else { "" }
+    }
+
+    /// Returns the line holding the failing span, its number and the column of the span
+    fn locate(&self) -> (&'s str, usize, usize) {
+        let mut line_start = 0;
+        let mut line = 1;
+        let mut column = 1;
+
+        for (i, (byte, c)) in self.source.char_indices().enumerate() {
+            if i == self.offset {
+                break;
+            }
+
+            if c == '\n' {
+                line_start = byte + 1;
+                line += 1;
+                column = 1;
+            }else{
+                column += 1;
+            }
+        }
+
+        let text = self.source[line_start..].lines().next().unwrap_or_default();
+        (text, line, column)
+    }
+}
+
+impl<'s> Display for Diagnostic<'s>{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (red, blue, bold, reset) = (self.style(RED), self.style(BLUE), self.style(BOLD), self.style(RESET));
+        let (text, line, column) = self.locate();
+        let gutter = " ".repeat(line.to_string().len());
+
+        writeln!(f, "{red}error{reset}{bold}: {}{reset}", self.message)?;
+        writeln!(f, "{gutter}{blue}-->{reset} line {line}, column {column}")?;
+        writeln!(f, "{gutter} {blue}|{reset}")?;
+        writeln!(f, "{blue}{line} |{reset} {text}")?;
+
+        // Keeps the tabs so the caret lines up with the text
+        let indent = text.chars().take(column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let length = self.length.min(text.chars().count().saturating_sub(column - 1)).max(1);
+        write!(f, "{gutter} {blue}|{reset} {indent}{red}{}", "^".repeat(length))?;
+
+        if !self.expected.is_empty() {
+            write!(f, " expected {}", join(&self.expected))?;
+        }
+        writeln!(f, "{reset}")?;
+
+        if !self.notes.is_empty() {
+            writeln!(f, "{gutter} {blue}|{reset}")?;
+        }
+
+        for note in &self.notes {
+            writeln!(f, "{gutter} {blue}={reset} {bold}note{reset}: {note}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lists what `error` expected
+fn expected(error: &PipelineError<'_, char>) -> Vec<String> {
+    match error {
+        PipelineError::WrongSymbol { expected, .. } => vec![format!("{expected:?}")],
+        PipelineError::WrongPattern { expected, .. } => vec![format!("{:?}", expected.iter().collect::<String>())],
+        PipelineError::SymbolNotMatchAnyOf { expected, .. } => expected.iter().map(|c| format!("{c:?}")).collect(),
+        PipelineError::NoMatchingAlternative { errors, .. } => {
+            let mut expected_set: Vec<String> = vec![];
+
+            for symbol in errors.iter().flat_map(expected) {
+                if !expected_set.contains(&symbol) {
+                    expected_set.push(symbol);
+                }
+            }
+
+            expected_set
+        },
+        PipelineError::SymbolNotInRange { expected, .. } => vec![format!("{expected:?}")],
+        PipelineError::SymbolNotInClass { class, .. } => vec![class.to_string()],
+        PipelineError::ExpectedEos { .. } => vec!["end of input".to_string()],
+        PipelineError::Context { source, .. } => expected(source),
+        _ => vec![]
+    }
+}
+
+/// Number of chars the failing span covers
+fn span_length(error: &PipelineError<'_, char>) -> usize {
+    match error {
+        PipelineError::WrongPattern { actual, .. } => actual.len(),
+        PipelineError::NotExpected { actual, .. } => actual.len().max(1),
+        PipelineError::NoMatchingAlternative { errors, .. } => errors.iter().map(span_length).max().unwrap_or(1),
+        PipelineError::Context { source, .. } => span_length(source),
+        _ => 1
+    }
+}
+
+/// Joins `items` as "a, b or c"
+fn join(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [rest @ .., last] => format!("{} or {last}", rest.join(", "))
+    }
+}
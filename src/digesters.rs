@@ -1,3 +1,5 @@
+use std::{error::Error, fmt::Display, marker::PhantomData, num::IntErrorKind};
+
 use crate::Symbol;
 
 /// Transforms a sequence of [Symbol] into a new type
@@ -8,15 +10,100 @@ pub trait Digester<S:Symbol>{
     fn digest(symbols: &[S]) -> Self::Output;
 }
 
+/// Transforms a sequence of [Symbol] into a new type, reporting malformed sequences
+pub trait TryDigester<S:Symbol>{
+    type Output;
+
+    /// Transforms [symbols](Symbol) into [Self::Output]
+    fn try_digest(symbols: &[S]) -> Result<Self::Output, DigestError>;
+}
+
+/// Transforms a sequence of [Symbol] into a new type, according to its configuration
+/// 
+/// Any `Fn(&[S]) -> T` closure is a stateful digester
+pub trait StatefulDigester<S:Symbol>{
+    type Output;
+
+    /// Transforms [symbols](Symbol) into [Self::Output]
+    fn digest(&self, symbols: &[S]) -> Self::Output;
+}
+
+impl<S:Symbol, T, F> StatefulDigester<S> for F
+where F: Fn(&[S]) -> T
+{
+    type Output = T;
+    fn digest(&self, symbols: &[S]) -> Self::Output {
+        self(symbols)
+    }
+}
+
+/// Why a sequence of symbols could not be digested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestError{
+    /// There is no digit
+    Empty,
+    /// A symbol is not a valid digit
+    InvalidDigit,
+    /// The number is too big for the output type
+    Overflow,
+    /// The number is too small for the output type
+    Underflow,
+    /// Any other reason, for user defined digesters
+    Invalid{ message: String }
+}
+
+impl Display for DigestError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Nothing to digest"),
+            Self::InvalidDigit => write!(f, "Invalid digit"),
+            Self::Overflow => write!(f, "Number too big for the output type"),
+            Self::Underflow => write!(f, "Number too small for the output type"),
+            Self::Invalid { message } => write!(f, "{message}")
+        }
+    }
+}
+
+impl Error for DigestError{}
+
+impl From<IntErrorKind> for DigestError{
+    fn from(value: IntErrorKind) -> Self {
+        match value {
+            IntErrorKind::Empty => Self::Empty,
+            IntErrorKind::PosOverflow => Self::Overflow,
+            IntErrorKind::NegOverflow => Self::Underflow,
+            _ => Self::InvalidDigit
+        }
+    }
+}
+
 pub struct IntDigester;
 pub struct StringDigester;
 
+/// Reads bytes as a big endian integer of type `T`
+/// 
+/// Fewer bytes than the size of `T` are zero extended, or sign extended when `T` is signed
+pub struct BigEndianDigester<T>(PhantomData<T>);
+
+/// Reads bytes as a little endian integer of type `T`
+/// 
+/// Fewer bytes than the size of `T` are zero extended, or sign extended when `T` is signed
+pub struct LittleEndianDigester<T>(PhantomData<T>);
+
 
 impl Digester<char> for IntDigester {
     type Output = isize;
     fn digest(symbols: &[char]) -> Self::Output {
-        let symbols = symbols.iter().collect::<String>();
-        isize::from_str_radix(&symbols, 10).expect(&format!("{symbols} is not base 10 !"))
+        Self::try_digest(symbols).unwrap_or_else(|_| panic!("{} is not base 10 !", symbols.iter().collect::<String>()))
+    }
+}
+
+impl TryDigester<char> for IntDigester {
+    type Output = isize;
+    fn try_digest(symbols: &[char]) -> Result<Self::Output, DigestError> {
+        symbols.iter().collect::<String>()
+            .parse::<isize>()
+            .map_err(|e| (*e.kind()).into())
     }
 }
 
@@ -27,3 +114,217 @@ impl Digester<char> for StringDigester {
     }
 }
 
+impl TryDigester<char> for StringDigester {
+    type Output = String;
+    fn try_digest(symbols: &[char]) -> Result<Self::Output, DigestError> {
+        Ok(Self::digest(symbols))
+    }
+}
+
+/// The byte filling the missing high bytes of an integer, given its most significant byte
+/// 
+/// It is `0xFF` for negative signed integers and `0` otherwise
+fn extension(signed: bool, high: Option<&u8>) -> u8 {
+    match high {
+        Some(high) if signed && high & 0x80 != 0 => 0xFF,
+        _ => 0
+    }
+}
+
+macro_rules! impl_endian_digesters {
+    ($($int:ty),*) => {
+        $(
+            impl Digester<u8> for BigEndianDigester<$int> {
+                type Output = $int;
+                fn digest(symbols: &[u8]) -> Self::Output {
+                    Self::try_digest(symbols).unwrap_or_else(|_| panic!("{} bytes do not fit in {}", symbols.len(), stringify!($int)))
+                }
+            }
+
+            impl TryDigester<u8> for BigEndianDigester<$int> {
+                type Output = $int;
+                fn try_digest(symbols: &[u8]) -> Result<Self::Output, DigestError> {
+                    const SIZE: usize = std::mem::size_of::<$int>();
+                    if symbols.len() > SIZE {
+                        return Err(DigestError::Overflow);
+                    }
+
+                    let mut bytes = [extension(<$int>::MIN != 0, symbols.first()); SIZE];
+                    bytes[SIZE - symbols.len()..].copy_from_slice(symbols);
+                    Ok(<$int>::from_be_bytes(bytes))
+                }
+            }
+
+            impl Digester<u8> for LittleEndianDigester<$int> {
+                type Output = $int;
+                fn digest(symbols: &[u8]) -> Self::Output {
+                    Self::try_digest(symbols).unwrap_or_else(|_| panic!("{} bytes do not fit in {}", symbols.len(), stringify!($int)))
+                }
+            }
+
+            impl TryDigester<u8> for LittleEndianDigester<$int> {
+                type Output = $int;
+                fn try_digest(symbols: &[u8]) -> Result<Self::Output, DigestError> {
+                    const SIZE: usize = std::mem::size_of::<$int>();
+                    if symbols.len() > SIZE {
+                        return Err(DigestError::Overflow);
+                    }
+
+                    let mut bytes = [extension(<$int>::MIN != 0, symbols.last()); SIZE];
+                    bytes[..symbols.len()].copy_from_slice(symbols);
+                    Ok(<$int>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_endian_digesters!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+/// Reads symbols as an integer of type `T` written in base `RADIX`
+/// 
+/// The number may start with a sign, then with the prefix of its radix (`0x`, `0o` or `0b`).
+/// Digits can be separated by `_`, as in `1_000`.
+pub struct RadixDigester<T, const RADIX: u32>(PhantomData<T>);
+
+pub type BinaryDigester<T> = RadixDigester<T, 2>;
+pub type OctalDigester<T> = RadixDigester<T, 8>;
+pub type DecimalDigester<T> = RadixDigester<T, 10>;
+pub type HexDigester<T> = RadixDigester<T, 16>;
+
+/// Splits the sign from the digits of a number written in base `radix`
+/// 
+/// Returns whether the number is negative and its digits, prefix and separators excluded
+fn split_number(symbols: &[char], radix: u32) -> Result<(bool, Vec<u32>), DigestError> {
+    let (negative, symbols) = match symbols {
+        ['-', rest @ ..] => (true, rest),
+        ['+', rest @ ..] => (false, rest),
+        _ => (false, symbols)
+    };
+
+    let symbols = match (radix, symbols) {
+        (16, ['0', 'x' | 'X', rest @ ..]) | (8, ['0', 'o' | 'O', rest @ ..]) | (2, ['0', 'b' | 'B', rest @ ..]) => rest,
+        _ => symbols
+    };
+
+    if symbols.is_empty() {
+        return Err(DigestError::Empty);
+    }
+
+    if symbols.first() == Some(&'_') || symbols.last() == Some(&'_') {
+        return Err(DigestError::InvalidDigit);
+    }
+
+    let digits = symbols.iter()
+        .filter(|c| **c != '_')
+        .map(|c| c.to_digit(radix).ok_or(DigestError::InvalidDigit))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((negative, digits))
+}
+
+macro_rules! impl_radix_digesters {
+    ($($int:ty),*) => {
+        $(
+            impl<const RADIX: u32> Digester<char> for RadixDigester<$int, RADIX> {
+                type Output = $int;
+                fn digest(symbols: &[char]) -> Self::Output {
+                    Self::try_digest(symbols).unwrap_or_else(|e| panic!("{} is not a base {RADIX} {}: {e}", symbols.iter().collect::<String>(), stringify!($int)))
+                }
+            }
+
+            impl<const RADIX: u32> TryDigester<char> for RadixDigester<$int, RADIX> {
+                type Output = $int;
+                fn try_digest(symbols: &[char]) -> Result<Self::Output, DigestError> {
+                    const { assert!(RADIX >= 2 && RADIX <= 36, "RADIX must be between 2 and 36") };
+
+                    let (negative, digits) = split_number(symbols, RADIX)?;
+                    if negative && <$int>::MIN == 0 {
+                        return Err(DigestError::InvalidDigit);
+                    }
+
+                    // Negative numbers are accumulated below zero so that MIN can be reached
+                    let mut value: $int = 0;
+                    for digit in digits {
+                        value = if negative {
+                            value.checked_mul(RADIX as $int).and_then(|v| v.checked_sub(digit as $int)).ok_or(DigestError::Underflow)?
+                        }else{
+                            value.checked_mul(RADIX as $int).and_then(|v| v.checked_add(digit as $int)).ok_or(DigestError::Overflow)?
+                        };
+                    }
+
+                    Ok(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_digesters!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Reads a [float literal](crate::MatchingPipeline::expect_float_literal) as a `T`, either [f32] or [f64]
+/// 
+/// Finite literals too big for `T` are reported as [DigestError::Overflow]
+pub struct FloatDigester<T>(PhantomData<T>);
+
+macro_rules! impl_float_digesters {
+    ($($float:ty),*) => {
+        $(
+            impl Digester<char> for FloatDigester<$float> {
+                type Output = $float;
+                fn digest(symbols: &[char]) -> Self::Output {
+                    Self::try_digest(symbols).unwrap_or_else(|e| panic!("{} is not a {}: {e}", symbols.iter().collect::<String>(), stringify!($float)))
+                }
+            }
+
+            impl TryDigester<char> for FloatDigester<$float> {
+                type Output = $float;
+                fn try_digest(symbols: &[char]) -> Result<Self::Output, DigestError> {
+                    if symbols.is_empty() {
+                        return Err(DigestError::Empty);
+                    }
+
+                    let literal = symbols.iter().collect::<String>();
+                    let value = literal.parse::<$float>().map_err(|_| DigestError::InvalidDigit)?;
+
+                    let is_infinity = literal.trim_start_matches(['+', '-']).to_lowercase().starts_with("inf");
+                    if value.is_infinite() && !is_infinity {
+                        return Err(DigestError::Overflow);
+                    }
+
+                    Ok(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_float_digesters!(f32, f64);
+
+/// Looks the symbols up in a table, to turn names into enum variants for instance
+pub struct LookupDigester<S:Symbol, T:Clone>{
+    table: Vec<(Vec<S>, T)>
+}
+
+impl<S:Symbol, T:Clone> LookupDigester<S, T>{
+    pub fn new(table: impl IntoIterator<Item = (Vec<S>, T)>) -> Self {
+        Self { table: table.into_iter().collect() }
+    }
+}
+
+impl<T:Clone> LookupDigester<char, T>{
+    /// Creates a table whose entries are named by strings
+    pub fn from_names<'n>(table: impl IntoIterator<Item = (&'n str, T)>) -> Self {
+        Self::new(table.into_iter().map(|(name, value)| (name.chars().collect(), value)))
+    }
+}
+
+impl<S:Symbol, T:Clone> StatefulDigester<S> for LookupDigester<S, T>{
+    type Output = Result<T, DigestError>;
+    fn digest(&self, symbols: &[S]) -> Self::Output {
+        self.table.iter()
+            .find(|(name, _)| name == symbols)
+            .map(|(_, value)| value.clone())
+            .ok_or_else(|| DigestError::Invalid { message: format!("{symbols:?} is not in the table") })
+    }
+}
@@ -0,0 +1,44 @@
+use std::borrow::Cow;
+
+use crate::{PipelineError, Symbol};
+
+/// Errors the combinators can fail with
+/// 
+/// Implement it for your own error type to fail with domain errors inside
+/// [blocks](crate::MatchingPipeline::block) and still use the `expect_*` methods with `?`
+pub trait MatchError<'a, S:Symbol+'a>: From<PipelineError<'a, S>> {
+    /// Returns the [PipelineError] this error wraps, or the error itself if it is a user error
+    fn into_pipeline_error(self) -> Result<PipelineError<'a, S>, Self>;
+
+    /// Labels the error, see [Context]
+    /// 
+    /// By default, only the errors wrapping a [PipelineError] are labeled
+    fn with_context(self, label: Cow<'a, str>) -> Self {
+        match self.into_pipeline_error() {
+            Ok(error) => PipelineError::Context { label, source: Box::new(error) }.into(),
+            Err(error) => error
+        }
+    }
+}
+
+impl<'a, S:Symbol> MatchError<'a, S> for PipelineError<'a, S>{
+    fn into_pipeline_error(self) -> Result<PipelineError<'a, S>, Self> {
+        Ok(self)
+    }
+}
+
+/// Labels the failures of a part of a pattern
+/// 
+/// Labels stack up, the outermost one being displayed first:
+/// `offset 4: while parsing header: while parsing key: Expected ':' but instead got '='`
+pub trait Context<'a, S:Symbol+'a>{
+    fn context(self, label: impl Into<Cow<'a, str>>) -> Self;
+}
+
+impl<'a, S:Symbol+'a, T, E> Context<'a, S> for Result<T, E>
+where E: MatchError<'a, S>
+{
+    fn context(self, label: impl Into<Cow<'a, str>>) -> Self {
+        self.map_err(|error| error.with_context(label.into()))
+    }
+}
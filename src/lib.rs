@@ -1,100 +1,365 @@
-use std::{error::Error, fmt::{Debug, Display}};
+use std::{borrow::{Borrow, Cow}, error::Error, fmt::{Debug, Display}, iter::once, ops::{Range, RangeInclusive}, rc::Rc};
+
+use position::LineTracker;
+use list::List;
+use source::Source;
+use caseless::Caseless;
+use unicode_normalization::UnicodeNormalization;
 
 #[cfg(test)]
 mod tests;
 
 mod quantifiers;
 mod digesters;
+mod list;
+mod captures;
+mod source;
+mod bytes;
+mod numbers;
+mod position;
+mod diagnostic;
+mod errors;
+mod separated;
+pub mod classes;
+pub mod comparators;
+mod regex;
+mod pattern;
+mod macros;
 pub use quantifiers::*;
 pub use digesters::*;
+pub use bytes::*;
+pub use position::{LineColumn, Position};
+pub use diagnostic::Diagnostic;
+pub use errors::*;
+pub use pattern::*;
+pub use captures::Capture;
+pub use separated::Separated;
+pub use regex::{Regex, RegexError, RegexErrorKind};
+
+pub trait Symbol:PartialEq+Clone+Debug{
+    /// Converts a char decoded from text input, which only [char] supports
+    #[doc(hidden)]
+    fn from_char(_: char) -> Option<Self> {
+        None
+    }
+}
 
-pub trait Symbol:PartialEq+Clone+Debug{}
+/// Tells if the symbol read from the input, the second argument,
+/// is equivalent to the expected one, the first argument
+/// 
+/// See [with_comparator](MatchingPipeline::with_comparator) and [comparators]
+pub type Comparator<S> = fn(&S, &S) -> bool;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 /// This structure helps you build a pattern matching pipeline
-pub struct MatchingPipeline<S:Symbol>{
-    matched: Vec<S>,
-    unmatched: Vec<S>,
-    reached_eos: bool,
-    offset:usize
+/// 
+/// The pipeline borrows its input and only moves cursors over it,
+/// so every step and every copy made for backtracking is O(1)
+pub struct MatchingPipeline<'i, S:Symbol>{
+    input: Source<'i, S>,
+    /// Matched ranges that were interrupted by a skip
+    segments: List<Range<usize>>,
+    /// Start of the range being matched, in units of the source
+    start: usize,
+    /// Current position, in units of the source
+    pos: usize,
+    /// Number of symbols read so far
+    offset:usize,
+    /// Line counter, if lines are tracked
+    lines: Option<LineTracker<S>>,
+    captures: List<captures::Record>,
+    compare: Comparator<S>
 }
 
+/// A pipeline that is done matching
+/// 
+/// Its matched and unmatched symbols are views into the original input whenever possible
 #[derive(Debug)]
-pub struct TerminatedPipeline<S:Symbol>{
-    matched:Vec<S>,
-    unmatched:Vec<S>,
-    offset: usize
+pub struct TerminatedPipeline<'i, S:Symbol>{
+    pipeline: MatchingPipeline<'i, S>
 }
 
-#[derive(Debug, PartialEq)]
+/// Every error tells the [Position] of the pipeline when it failed
+/// 
+/// The expected symbols are borrowed from the pattern, see [into_owned](PipelineError::into_owned)
+/// to get rid of this lifetime
+#[derive(Debug, Clone, PartialEq)]
 pub enum PipelineError<'a, S:Symbol>{
-    UnexpectedEos,
+    UnexpectedEos{ position: Position },
     WrongSymbol{
-        expected: &'a S,
-        actual: S
+        expected: Cow<'a, S>,
+        actual: S,
+        position: Position
     },
     WrongPattern{
-        expected: &'a [S],
-        actual: Vec<S>
+        expected: Cow<'a, [S]>,
+        actual: Vec<S>,
+        position: Position
     },
 
 
     SymbolNotMatchAnyOf{
-        expected: &'a [S],
-        actual: S
+        expected: Cow<'a, [S]>,
+        actual: S,
+        position: Position
     },
 
-    SymbolNotMatchingPredicate{actual: S},
+    SymbolNotMatchingPredicate{actual: S, position: Position},
+
+    SymbolNotInRange{
+        expected: RangeInclusive<S>,
+        actual: S,
+        position: Position
+    },
+
+    /// The symbol is not part of a named class, see [classes]
+    SymbolNotInClass{
+        class: Cow<'a, str>,
+        actual: S,
+        position: Position
+    },
+
+    Unexpected{ message: Cow<'a, str>, position: Position },
+
+    /// Symbols were left where the end of the input was expected
+    ExpectedEos{ actual: S, position: Position },
+
+    /// A [negative lookahead](MatchingPipeline::not) matched `actual`
+    NotExpected{ actual: Vec<S>, position: Position },
+
+    /// Every branch of an alternation failed
+    /// 
+    /// Only the errors of the branches that went the furthest are kept
+    NoMatchingAlternative{ errors: Vec<PipelineError<'a, S>>, position: Position },
+
+    /// An error that occurred in a labeled part of the pattern, see [Context]
+    Context{ label: Cow<'a, str>, source: Box<PipelineError<'a, S>> }
+
+}
+
+impl<'a, S:Symbol> PipelineError<'a, S>{
+    /// Where the pipeline failed
+    pub fn position(&self) -> Position {
+        match self {
+            Self::UnexpectedEos { position }
+            | Self::WrongSymbol { position, .. }
+            | Self::WrongPattern { position, .. }
+            | Self::SymbolNotMatchAnyOf { position, .. }
+            | Self::SymbolNotMatchingPredicate { position, .. }
+            | Self::SymbolNotInRange { position, .. }
+            | Self::SymbolNotInClass { position, .. }
+            | Self::Unexpected { position, .. }
+            | Self::NotExpected { position, .. }
+            | Self::ExpectedEos { position, .. }
+            | Self::NoMatchingAlternative { position, .. } => *position,
+            Self::Context { source, .. } => source.position()
+        }
+    }
+
+    /// Copies whatever the error borrows from the pattern
+    /// 
+    /// The owned error can be returned from functions building their patterns at runtime
+    /// and converted into `Box<dyn Error + Send + Sync>`
+    pub fn into_owned(self) -> OwnedPipelineError<S> where S: 'static {
+        match self {
+            Self::UnexpectedEos { position } => PipelineError::UnexpectedEos { position },
+            Self::WrongSymbol { expected, actual, position } => PipelineError::WrongSymbol { expected: Cow::Owned(expected.into_owned()), actual, position },
+            Self::WrongPattern { expected, actual, position } => PipelineError::WrongPattern { expected: Cow::Owned(expected.into_owned()), actual, position },
+            Self::SymbolNotMatchAnyOf { expected, actual, position } => PipelineError::SymbolNotMatchAnyOf { expected: Cow::Owned(expected.into_owned()), actual, position },
+            Self::SymbolNotMatchingPredicate { actual, position } => PipelineError::SymbolNotMatchingPredicate { actual, position },
+            Self::SymbolNotInRange { expected, actual, position } => PipelineError::SymbolNotInRange { expected, actual, position },
+            Self::SymbolNotInClass { class, actual, position } => PipelineError::SymbolNotInClass { class: Cow::Owned(class.into_owned()), actual, position },
+            Self::Unexpected { message, position } => PipelineError::Unexpected { message: Cow::Owned(message.into_owned()), position },
+            Self::NotExpected { actual, position } => PipelineError::NotExpected { actual, position },
+            Self::ExpectedEos { actual, position } => PipelineError::ExpectedEos { actual, position },
+            Self::NoMatchingAlternative { errors, position } => PipelineError::NoMatchingAlternative {
+                errors: errors.into_iter().map(PipelineError::into_owned).collect(),
+                position
+            },
+            Self::Context { label, source } => PipelineError::Context { label: Cow::Owned(label.into_owned()), source: Box::new(source.into_owned()) }
+        }
+    }
+
+    /// The error message, without its position
+    pub fn message(&self) -> String {
+        struct Message<'e, 'a, S:Symbol>(&'e PipelineError<'a, S>);
+
+        impl<'e, 'a, S:Symbol> Display for Message<'e, 'a, S>{
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.describe(f)
+            }
+        }
+
+        Message(self).to_string()
+    }
 
-    Unexpected{ message: &'a str }
+    /// Describes the error, without its position
+    fn describe(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self{
+            Self::UnexpectedEos { .. } => write!(f, "Unexpected end of stream"),
+            Self::WrongSymbol { expected, actual, .. } => write!(f, "Expected {expected:?} but instead got {actual:?}"),
+            Self::WrongPattern { expected, actual, .. } => write!(f, "Expected pattern {expected:?} but instead got {actual:?}"),
+            Self::SymbolNotMatchAnyOf { expected, actual, .. } => write!(f, "Expected one of {expected:?} but instead got {actual:?}"),
+            Self::SymbolNotMatchingPredicate { actual, .. } => write!(f, "{actual:?} does not match the given predicate"),
+            Self::SymbolNotInRange { expected, actual, .. } => write!(f, "Expected a symbol in {expected:?} but instead got {actual:?}"),
+            Self::SymbolNotInClass { class, actual, .. } => write!(f, "Expected {class} but instead got {actual:?}"),
+            Self::Unexpected{message, ..} => write!(f, "Unexpected error: {message}"),
+            Self::NotExpected { actual, .. } => write!(f, "Did not expect {actual:?}"),
+            Self::ExpectedEos { actual, .. } => write!(f, "Expected the end of stream but instead got {actual:?}"),
+            Self::NoMatchingAlternative { errors, .. } => {
+                write!(f, "None of the alternatives matched")?;
+
+                for (i, error) in errors.iter().enumerate() {
+                    write!(f, "{} ", if i == 0 { ":" } else { " or" })?;
+                    error.describe(f)?;
+                }
 
+                Ok(())
+            },
+            Self::Context { label, source } => {
+                write!(f, "{label}: ")?;
+                source.describe(f)
+            }
+        }
+    }
 }
 
 
 impl<'a, S:Symbol> Display for PipelineError<'a, S>{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &self{
-            &Self::UnexpectedEos => write!(f, "Unexpected end of stream"),
-            &Self::WrongSymbol { expected, actual } => write!(f, "Expected {expected:?} but instead got {actual:?}"),
-            &Self::WrongPattern { expected, actual } => write!(f, "Expected pattern {expected:?} but instead got {actual:?}"),
-            &Self::SymbolNotMatchAnyOf { expected, actual } => write!(f, "Expected one of {expected:?} but instead got {actual:?}"),
-            &Self::SymbolNotMatchingPredicate { actual } => write!(f, "{actual:?} does not match the given predicate"),
-            &Self::Unexpected{message} => write!(f, "Unexpected error: {message}")
-        }
+        write!(f, "{}: ", self.position())?;
+        self.describe(f)
     }
 }
 
 impl<'a, S:Symbol> Error for PipelineError<'a, S>{}
 
-pub type PipelineResult<'a, Symbol> = Result<MatchingPipeline<Symbol>, PipelineError<'a, Symbol>>;
+/// A [PipelineError] that does not borrow anything
+pub type OwnedPipelineError<S> = PipelineError<'static, S>;
 
+/// The result of a pipeline step
+/// 
+/// `E` can be a user error type, see [MatchError]
+pub type PipelineResult<'i, 'a, Symbol, E = PipelineError<'a, Symbol>> = Result<MatchingPipeline<'i, Symbol>, E>;
 
 
-impl<'a, S:Symbol> MatchingPipeline<S>{
+
+impl<'i, 'a, S:Symbol+'a> MatchingPipeline<'i, S>{
     pub fn new(candidate: impl IntoIterator<Item = S>) -> Self{
-        let collection = candidate.into_iter().collect::<Vec<S>>();
-        Self { matched: vec![], reached_eos: collection.is_empty(), unmatched: collection, offset: 0  }
+        Self::from_source(Source::Shared(candidate.into_iter().collect()))
     }
 
-    /// Matches the current symbol:
+    fn from_source(input: Source<'i, S>) -> Self {
+        Self { input, segments: List::default(), start: 0, pos: 0, offset: 0, lines: None, captures: List::default(), compare: S::eq }
+    }
+
+    /// Tracks lines and columns, so that errors and the [TerminatedPipeline] tell them
     /// 
-    /// The symbol is added to the list of matched symbols
-    /// and the pipeline moves to the next symbol of the sequence
-    pub fn consume(mut self) -> Self {
-        if self.reached_eos {
-            return self;
+    /// * `newline` - The symbol that ends a line
+    pub fn track_lines(mut self, newline: S) -> Self {
+        let mut lines = LineTracker::new(newline);
+        let (mut pos, mut offset) = (0, 0);
+
+        while let Some((symbol, width)) = self.input.get(pos).filter(|_| pos < self.pos) {
+            pos += width;
+            offset += 1;
+            lines.track(&symbol, offset);
+        }
+
+        self.lines = Some(lines);
+        self
+    }
+
+    /// Compares symbols with `compare` rather than [PartialEq]
+    /// 
+    /// The comparator is used by [expect_symbol](Self::expect_symbol), [expect_pattern](Self::expect_pattern),
+    /// [expect_any_of](Self::expect_any_of) and [match_until](Self::match_until)
+    pub fn with_comparator(mut self, compare: Comparator<S>) -> Self {
+        self.compare = compare;
+        self
+    }
+
+    /// The current position of the pipeline
+    pub fn position(&self) -> Position {
+        Position {
+            offset: self.offset,
+            line_column: self.lines.as_ref().map(|lines| lines.line_column(self.offset))
+        }
+    }
+
+    /// Tells if every symbol of the input was read
+    pub fn is_eos(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    /// Number of symbols read so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The current symbol, if any, without consuming it
+    pub fn peek(&self) -> Option<Cow<'_, S>> {
+        self.current().map(|(symbol, _)| symbol)
+    }
+
+    /// Up to `n` symbols from the current one, without consuming them
+    pub fn peek_n(&self, n: usize) -> Cow<'i, [S]> {
+        self.input.slice(self.pos..self.input.end(self.pos, n))
+    }
+
+    /// Returns the current symbol and its width in units of the source
+    fn current(&self) -> Option<(Cow<'_, S>, usize)> {
+        self.input.get(self.pos)
+    }
+
+    /// Moves past `width` units holding `symbols` symbols
+    fn advance(&mut self, width: usize, symbols: usize) {
+        if let Some(lines) = &mut self.lines {
+            let (mut pos, mut offset) = (self.pos, self.offset);
+
+            while pos < self.pos + width {
+                let (symbol, width) = self.input.get(pos).expect("Advancing over existing symbols");
+                pos += width;
+                offset += 1;
+                lines.track(&symbol, offset);
+            }
         }
 
-        let (matched, unmatched) = self.unmatched.split_at(1);
+        self.pos += width;
+        self.offset += symbols;
+    }
 
-        self.matched.append(&mut matched.to_vec());
-        self.unmatched = unmatched.to_vec();
+    /// The matched symbols, leaving out the skipped ones
+    /// 
+    /// They are borrowed from the input when it is a slice and nothing was skipped,
+    /// text is decoded into a new `Vec`, see [matched_str](Self::matched_str)
+    pub fn matched(&self) -> Cow<'i, [S]> {
+        if self.segments.is_empty() {
+            return self.input.slice(self.start..self.pos);
+        }
 
-        if !self.reached_eos {
-            self.offset += 1;
+        let mut matched = vec![];
+        for range in self.segments.items() {
+            matched.extend_from_slice(&self.input.slice(range));
         }
+        matched.extend_from_slice(&self.input.slice(self.start..self.pos));
 
-        self.reached_eos = self.unmatched.is_empty();
+        Cow::Owned(matched)
+    }
+
+    /// The symbols not matched yet
+    pub fn remaining(&self) -> Cow<'i, [S]> {
+        self.input.slice(self.pos..self.input.len())
+    }
+
+    /// Matches the current symbol:
+    /// 
+    /// The symbol is added to the list of matched symbols
+    /// and the pipeline moves to the next symbol of the sequence
+    pub fn consume(mut self) -> Self {
+        if let Some((_, width)) = self.current() {
+            self.advance(width, 1);
+        }
 
         self
     }
@@ -103,17 +368,15 @@ impl<'a, S:Symbol> MatchingPipeline<S>{
     /// 
     /// The current symbol is not added to the matched symbols list
     pub fn skip(mut self) -> Self {
-        if self.reached_eos {
+        let Some((_, width)) = self.current() else {
             return self;
-        }
-
-        self.unmatched = self.unmatched.get(1..).unwrap_or_default().to_vec();
+        };
 
-        if !self.reached_eos {
-            self.offset += 1;
+        if self.start < self.pos {
+            self.segments.push(self.start..self.pos);
         }
-
-        self.reached_eos = self.unmatched.is_empty();
+        self.advance(width, 1);
+        self.start = self.pos;
 
         self
     }
@@ -121,60 +384,85 @@ impl<'a, S:Symbol> MatchingPipeline<S>{
     /// Expects that `symbol` can be matched
     /// 
     /// * `symbol` - The expected symbol
-    pub fn expect_symbol(self, symbol:&'a S) -> PipelineResult<'a, S>{
-        if self.reached_eos {
-            return Err(PipelineError::UnexpectedEos);
+    pub fn expect_symbol(self, symbol:&'a S) -> PipelineResult<'i, 'a, S>{
+        self.expect_symbol_cow(Cow::Borrowed(symbol))
+    }
+
+    /// Same as [expect_symbol](Self::expect_symbol), for a symbol that may not be borrowed
+    pub(crate) fn expect_symbol_cow(mut self, symbol: Cow<'a, S>) -> PipelineResult<'i, 'a, S>{
+        let Some((actual, width)) = self.current() else {
+            return Err(PipelineError::UnexpectedEos { position: self.position() });
+        };
+
+        if !(self.compare)(&symbol, &actual) {
+            return Err(PipelineError::WrongSymbol { expected: symbol, actual: actual.into_owned(), position: self.position() });
         }
 
-        let actual = self.unmatched.get(0).unwrap().clone();
-        if symbol == &actual {
-            return  Ok(self.consume());
+        self.advance(width, 1);
+        Ok(self)
+    }
+
+    /// Expects that every symbol of the input was read
+    pub fn expect_eos(self) -> PipelineResult<'i, 'a, S> {
+        match self.current() {
+            Some((actual, _)) => Err(PipelineError::ExpectedEos { actual: actual.into_owned(), position: self.position() }),
+            None => Ok(self)
         }
+    }
 
-        Err(PipelineError::WrongSymbol { expected: &symbol, actual})
-        
+    /// Tells if the input continues with `pattern`
+    /// and returns the position right after it along with the number of symbols
+    pub(crate) fn find_pattern(&self, pattern: impl IntoIterator<Item = impl Borrow<S>>) -> Option<(usize, usize)> {
+        let (mut pos, mut symbols) = (self.pos, 0);
+
+        for expected in pattern {
+            match self.input.get(pos) {
+                Some((actual, width)) if (self.compare)(expected.borrow(), &actual) => pos += width,
+                _ => return None
+            }
+
+            symbols += 1;
+        }
+
+        Some((pos, symbols))
     }
 
     /// Expects that `pattern` can be matched
     /// 
     /// * `pattern` - The expected pattern
-    pub fn expect_pattern(mut self, pattern:&'a [S]) -> PipelineResult<'a, S>{
-        let pipeline = self.clone();
-        match pipeline.unmatched.get(0..pattern.len()) {
-            Some(symbols) if symbols == pattern => {
-                for symbol in symbols{
-                    //Todo: replace with consume()
-                    self = self.expect_symbol(symbol).expect("All symbols should match");
-                }
+    pub fn expect_pattern(self, pattern:&'a [S]) -> PipelineResult<'i, 'a, S>{
+        self.expect_pattern_cow(Cow::Borrowed(pattern))
+    }
 
+    /// Same as [expect_pattern](Self::expect_pattern), for a pattern that may not be borrowed
+    pub(crate) fn expect_pattern_cow(mut self, pattern: Cow<'a, [S]>) -> PipelineResult<'i, 'a, S>{
+        match self.find_pattern(pattern.iter()) {
+            Some((end, symbols)) => {
+                self.advance(end - self.pos, symbols);
                 Ok(self)
             },
 
-            Some(s) => {
-                Err(PipelineError::WrongPattern { expected: pattern, actual: s.to_vec() })
-            },
-
-            None => Err(PipelineError::WrongPattern { expected: pattern, actual: pipeline.unmatched.to_vec() })
+            None => {
+                let (actual, _) = self.input.take(self.pos, pattern.len());
+                Err(PipelineError::WrongPattern { expected: pattern, actual, position: self.position() })
+            }
         }
     }
 
     /// Expects that `symbols` contains the current symbol 
     /// 
     /// * `symbols` - A list of symbols
-    pub fn expect_any_of(mut self, symbols:&'a [S]) -> PipelineResult<'a, S> {
-        if self.reached_eos {
-            return Err(PipelineError::UnexpectedEos);
-        }
-        let actual = self.unmatched.get(0).unwrap().clone();
+    pub fn expect_any_of(mut self, symbols:&'a [S]) -> PipelineResult<'i, 'a, S> {
+        let Some((actual, width)) = self.current() else {
+            return Err(PipelineError::UnexpectedEos { position: self.position() });
+        };
 
-        if symbols.contains(&actual) {
-
-            self = self.consume();
-
-            return Ok(self);
+        if !symbols.iter().any(|symbol| (self.compare)(symbol, &actual)) {
+            return Err(PipelineError::SymbolNotMatchAnyOf { expected: Cow::Borrowed(symbols), actual: actual.into_owned(), position: self.position() });
         }
 
-        Err(PipelineError::SymbolNotMatchAnyOf { expected: symbols, actual })
+        self.advance(width, 1);
+        Ok(self)
     }
 
 
@@ -186,14 +474,12 @@ impl<'a, S:Symbol> MatchingPipeline<S>{
     pub fn match_until(mut self, delim:&'a [S], match_delim:bool) -> Self {
     
         loop {
-            if self.reached_eos {
+            if self.is_eos() {
                 break;
             }
 
-            let pipeline = self.clone();
-            
-            if let Ok(s) = pipeline.expect_pattern(delim){
-                if match_delim { self = s; }
+            if let Some((end, symbols)) = self.find_pattern(delim) {
+                if match_delim { self.advance(end - self.pos, symbols); }
                 break;
             }
             self = self.consume();
@@ -206,137 +492,438 @@ impl<'a, S:Symbol> MatchingPipeline<S>{
 
     /// Matches all symbols until it reaches end of stream
     pub fn match_until_eos(mut self) -> Self {
-    
-        loop {
-            if self.reached_eos {
-                break;
-            }
-
-            self = self.consume();
-
-            
-        }
+        let end = self.input.len();
+        self.advance(end - self.pos, self.input.count(self.pos..end));
 
         self
     }
 
     /// Expects that the current symbol matches the predicate.
-    pub fn expect_predicate<F>(mut self, predicate: F) -> PipelineResult<'a, S>
+    pub fn expect_predicate<F>(mut self, predicate: F) -> PipelineResult<'i, 'a, S>
     where F: Fn(&S) -> bool
     {
-        if self.reached_eos{
-            return Err(PipelineError::UnexpectedEos);
-        }
+        let Some((actual, width)) = self.current() else {
+            return Err(PipelineError::UnexpectedEos { position: self.position() });
+        };
 
-        if predicate(&self.unmatched[0]) {
-            self = self.consume();
-            return Ok(self);
+        if !predicate(&actual) {
+            return Err(PipelineError::SymbolNotMatchingPredicate { actual: actual.into_owned(), position: self.position() });
         }
 
-        Err(PipelineError::SymbolNotMatchingPredicate { actual: self.unmatched[0].clone() })
+        self.advance(width, 1);
+        Ok(self)
     }
 
     /// Matches all symbols until predicate fail or reaches end of stream.
     pub fn match_while_true<F>(mut self, predicate: F) -> Self
     where F: Fn(&S) -> bool
     {
-        loop {
-            if self.reached_eos {
+        while let Some((symbol, width)) = self.current() {
+            if !predicate(&symbol) {
                 break;
             }
 
-            if predicate(&self.unmatched[0]) {
-                self = self.consume();
-            }else{
-                break;
-            }
+            self.advance(width, 1);
         }
 
         self
     }
 
+    /// Runs `callback` but leaves the symbols it reads out of the matched ones
+    /// 
+    /// The input is still validated by `callback`, and the captures it records are kept
+    pub fn discard<F, E>(self, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E> {
+        let pipeline = self.clone().block(callback)?;
+        Ok(self.discarding(pipeline))
+    }
+
+    /// Expects `pattern`, like [expect_pattern](Self::expect_pattern), without matching it
+    pub fn skip_pattern(self, pattern:&'a [S]) -> PipelineResult<'i, 'a, S> {
+        self.discard(|p| p.expect_pattern(pattern))
+    }
+
+    /// Skips symbols as long as they match the predicate, see [match_while_true](Self::match_while_true)
+    pub fn skip_while<F>(self, predicate: F) -> Self where F: Fn(&S) -> bool {
+        let pipeline = self.clone().match_while_true(predicate);
+        self.discarding(pipeline)
+    }
+
+    /// Skips symbols until the pattern `delim` or the end of stream, see [match_until](Self::match_until)
+    /// 
+    /// * `skip_delim` - If the delimiter is skipped as well
+    pub fn skip_until(self, delim:&'a [S], skip_delim:bool) -> Self {
+        let pipeline = self.clone().match_until(delim, skip_delim);
+        self.discarding(pipeline)
+    }
+
+    /// Returns `pipeline`, which went on from `self`, with the symbols read since `self` left out
+    fn discarding(self, mut pipeline: Self) -> Self {
+        pipeline.segments = self.segments;
+        if self.start < self.pos {
+            pipeline.segments.push(self.start..self.pos);
+        }
+        pipeline.start = pipeline.pos;
+
+        pipeline
+    }
+
+    /// Expects that `pattern` matches, see [Pattern]
+    pub fn expect<P, E>(self, pattern: P) -> PipelineResult<'i, 'a, S, E> where P: Pattern<'i, 'a, S, E> {
+        pattern.apply(self)
+    }
+
     /// Encapsulates the logic inside a closure
-    pub fn block<F>(self, callback: F) -> PipelineResult<'a, S> where F: Fn(Self) -> PipelineResult<'a, S> {
+    /// 
+    /// The closure may fail with a user error type, see [MatchError].
+    /// Any other [Pattern] can be matched with [expect](Self::expect)
+    pub fn block<F, E>(self, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E> {
         callback(self)
     }
 
-    pub fn terminate(self) -> TerminatedPipeline<S> {
-        TerminatedPipeline{
-            unmatched: self.unmatched,
-            matched: self.matched,
-            offset: self.offset
+    /// Tries each branch from the current position and keeps the first one that matches
+    /// 
+    /// If none of them matches, the errors of the branches that went the furthest
+    /// are merged in a [PipelineError::NoMatchingAlternative].
+    /// A branch failing with a user error stops the alternation right away.
+    /// 
+    /// * `branches` - The alternatives, in order of preference
+    pub fn one_of<E, const N: usize>(self, branches: [&dyn Fn(Self) -> PipelineResult<'i, 'a, S, E>; N]) -> PipelineResult<'i, 'a, S, E>
+    where E: MatchError<'a, S>
+    {
+        let mut errors = vec![];
+        let mut position = self.position();
+
+        for branch in branches {
+            let error = match self.clone().block(branch).map_err(E::into_pipeline_error) {
+                Ok(pipeline) => return Ok(pipeline),
+                Err(Ok(error)) => error,
+                Err(Err(error)) => return Err(error)
+            };
+
+            if error.position().offset > position.offset || errors.is_empty() {
+                position = error.position();
+                errors.clear();
+            }else if error.position().offset < position.offset {
+                continue;
+            }
+
+            match error {
+                PipelineError::NoMatchingAlternative { errors: nested, .. } => errors.extend(nested),
+                error => errors.push(error)
+            }
+        }
+
+        Err(PipelineError::NoMatchingAlternative { errors, position }.into())
+    }
+
+    /// Tries `first`, then `second` from the same position if it failed
+    /// 
+    /// See [one_of](Self::one_of)
+    pub fn or<F, G, E>(self, first: F, second: G) -> PipelineResult<'i, 'a, S, E>
+    where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, G: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>
+    {
+        self.one_of([&first, &second])
+    }
+
+    /// Succeeds without consuming anything if `callback` matches from the current position
+    pub fn lookahead<F, E>(self, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E> {
+        self.clone().block(callback)?;
+        Ok(self)
+    }
+
+    /// Succeeds without consuming anything if `callback` does not match from the current position
+    /// 
+    /// Fails with a [PipelineError::NotExpected] holding what `callback` matched.
+    /// A user error from `callback` is passed on, see [MatchError].
+    pub fn not<F, E>(self, callback: F) -> PipelineResult<'i, 'a, S, E>
+    where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>
+    {
+        match self.clone().block(callback).map_err(E::into_pipeline_error) {
+            Ok(pipeline) => Err(PipelineError::NotExpected {
+                actual: self.input.slice(self.pos..pipeline.pos).into_owned(),
+                position: self.position()
+            }.into()),
+            Err(Ok(_)) => Ok(self),
+            Err(Err(error)) => Err(error)
         }
     }
 
+    pub fn terminate(self) -> TerminatedPipeline<'i, S> {
+        TerminatedPipeline{ pipeline: self }
+    }
+
     
 }
 
-impl<S:Symbol> TerminatedPipeline<S>{
+impl<'i, 'a, S:Symbol+PartialOrd+'a> MatchingPipeline<'i, S>{
+    /// Expects that the current symbol is within `range`
+    /// 
+    /// * `range` - The expected symbols, like `'a'..='z'`
+    pub fn expect_range(mut self, range: RangeInclusive<S>) -> PipelineResult<'i, 'a, S> {
+        let Some((actual, width)) = self.current() else {
+            return Err(PipelineError::UnexpectedEos { position: self.position() });
+        };
+
+        if !range.contains(actual.as_ref()) {
+            return Err(PipelineError::SymbolNotInRange { expected: range, actual: actual.into_owned(), position: self.position() });
+        }
+
+        self.advance(width, 1);
+        Ok(self)
+    }
+}
+
+impl<'i> MatchingPipeline<'i, char>{
+    /// The matched text, borrowed from the input when it is a contiguous piece of text
+    /// 
+    /// It is a [Cow] rather than a `&str` since the text is collected into a new `String`
+    /// when symbols were skipped in the middle of the match, or when the input is not a `&str`,
+    /// like a `String` or a slice of chars.
+    /// See [matched_span_str](Self::matched_span_str) for a slice that never allocates
+    pub fn matched_str(&self) -> Cow<'i, str> {
+        match self.matched_span_str() {
+            Some(text) => Cow::Borrowed(text),
+            None => Cow::Owned(self.matched().iter().collect())
+        }
+    }
+
+    /// The matched text as a slice of the input
+    /// 
+    /// `None` when the input is not text or symbols were skipped in the middle of the match
+    pub fn matched_span_str(&self) -> Option<&'i str> {
+        if !self.segments.is_empty() {
+            return None;
+        }
+
+        self.input.text(self.start..self.pos)
+    }
+
+    /// The text not matched yet, borrowed from the input when it is text
+    pub fn remaining_str(&self) -> Cow<'i, str> {
+        match self.input.text(self.pos..self.input.len()) {
+            Some(text) => Cow::Borrowed(text),
+            None => Cow::Owned(self.remaining().iter().collect())
+        }
+    }
+}
+
+/// Pipelines are equal when they matched the same symbols, have the same symbols left
+/// and read as many symbols
+impl<'i, S:Symbol> PartialEq for MatchingPipeline<'i, S>{
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset
+        && self.matched() == other.matched()
+        && self.remaining() == other.remaining()
+    }
+}
+
+impl<'i, S:Symbol> TerminatedPipeline<'i, S>{
 
-    pub fn matched(&self) -> &[S]{
-        &self.matched
+    /// The matched symbols
+    /// 
+    /// They are borrowed from the input unless symbols were skipped
+    /// or the input had to be decoded
+    pub fn matched(&self) -> Cow<'i, [S]>{
+        self.pipeline.matched()
     }
 
-    pub fn unmatched(&self) -> &[S]{
-        &self.unmatched
+    /// The symbols left after the match
+    pub fn unmatched(&self) -> Cow<'i, [S]>{
+        self.pipeline.remaining()
     }
 
     pub fn offset(&self) -> usize {
-        self.offset
+        self.pipeline.offset
+    }
+
+    /// Where the pipeline stopped matching
+    pub fn position(&self) -> Position {
+        self.pipeline.position()
     }
 
     pub fn digest<D>(self) -> <D as Digester<S>>::Output
     where D: Digester<S>
     {
-        D::digest(&self.matched)
+        D::digest(&self.matched())
+    }
+
+    /// Digests the matched symbols with a configured digester
+    pub fn digest_with<D>(self, digester: &D) -> <D as StatefulDigester<S>>::Output
+    where D: StatefulDigester<S>
+    {
+        digester.digest(&self.matched())
+    }
+
+    /// Digests the matched symbols, reporting malformed ones instead of panicking
+    pub fn try_digest<D>(self) -> Result<<D as TryDigester<S>>::Output, DigestError>
+    where D: TryDigester<S>
+    {
+        D::try_digest(&self.matched())
+    }
+}
+
+impl<'i> TerminatedPipeline<'i, char>{
+    /// The matched text
+    /// 
+    /// When the pipeline reads a `&str`, this is a slice of it at the right UTF-8 byte offsets,
+    /// unless symbols were skipped in the middle of the match.
+    /// Then, or when the input is not a `&str`, the text is collected into a new `String`:
+    /// use [matched_span_str](Self::matched_span_str) to never allocate.
+    pub fn matched_str(&self) -> Cow<'i, str> {
+        self.pipeline.matched_str()
+    }
+
+    /// The matched text as a slice of the input, never allocating
+    /// 
+    /// `None` when the input is not text or symbols were skipped in the middle of the match
+    pub fn matched_span_str(&self) -> Option<&'i str> {
+        self.pipeline.matched_span_str()
+    }
+
+    /// The text left after the match
+    pub fn unmatched_str(&self) -> Cow<'i, str> {
+        self.pipeline.remaining_str()
+    }
+}
+
+pub trait Matchable<'i, S:Symbol+'i>: Into<MatchingPipeline<'i, S>> {}
+
+impl Symbol for char{
+    fn from_char(c: char) -> Option<Self> {
+        Some(c)
+    }
+}
+impl Symbol for u8{}
+
+impl<'i, S:Symbol+'i, T: Into<MatchingPipeline<'i, S>>> Matchable<'i, S> for T{}
+
+impl<'i> From<&'i str> for MatchingPipeline<'i, char>{
+    fn from(value: &'i str) -> Self {
+        MatchingPipeline::from_source(Source::Text(value))
+    }
+}
+
+impl<'i> From<&'i String> for MatchingPipeline<'i, char>{
+    fn from(value: &'i String) -> Self {
+        value.as_str().into()
     }
 }
 
-pub trait Matchable<S:Symbol>: Into<MatchingPipeline<S>> {}
+impl<'i> From<String> for MatchingPipeline<'i, char>{
+    fn from(value: String) -> Self {
+        MatchingPipeline::new(value.chars())
+    }
+}
 
-impl Symbol for char{}
+impl<'i, S:Symbol> From<&'i [S]> for MatchingPipeline<'i, S>{
+    fn from(value: &'i [S]) -> Self {
+        MatchingPipeline::from_source(Source::Slice(value))
+    }
+}
 
-impl<T: AsRef<str>> Matchable<char> for T{}
+impl<'i, S:Symbol, const N: usize> From<&'i [S; N]> for MatchingPipeline<'i, S>{
+    fn from(value: &'i [S; N]) -> Self {
+        MatchingPipeline::from_source(Source::Slice(value))
+    }
+}
 
-impl<T: AsRef<str>> From<T> for MatchingPipeline<char>{
-    fn from(value: T) -> Self {
-        MatchingPipeline::new(value.as_ref().chars())
+impl<'i, S:Symbol> From<&'i Vec<S>> for MatchingPipeline<'i, S>{
+    fn from(value: &'i Vec<S>) -> Self {
+        MatchingPipeline::from_source(Source::Slice(value))
+    }
+}
+
+impl<'i, S:Symbol> From<Vec<S>> for MatchingPipeline<'i, S>{
+    fn from(value: Vec<S>) -> Self {
+        MatchingPipeline::from_source(Source::Shared(Rc::from(value)))
+    }
+}
+
+impl<'i, S:Symbol> From<Cow<'i, [S]>> for MatchingPipeline<'i, S>{
+    fn from(value: Cow<'i, [S]>) -> Self {
+        match value {
+            Cow::Borrowed(symbols) => symbols.into(),
+            Cow::Owned(symbols) => symbols.into()
+        }
     }
 }
 
+impl<'i, S:Symbol, const N: usize> From<[S; N]> for MatchingPipeline<'i, S>{
+    fn from(value: [S; N]) -> Self {
+        MatchingPipeline::new(value)
+    }
+}
 
 
 
 
 /// Creates a [MatchingPipeline]
-pub fn begin_match<S>(candidate: impl Matchable<S>) -> MatchingPipeline<S> where S:Symbol{
+pub fn begin_match<'i, S>(candidate: impl Matchable<'i, S>) -> MatchingPipeline<'i, S> where S:Symbol+'i{
     candidate.into()
 }
 
+/// Creates a [MatchingPipeline] over the canonical composition (NFC) of `text`
+/// 
+/// Canonically equivalent text then reads the same, like `é` and `e` followed by U+0301.
+/// Offsets count the composed chars and the matched text is no longer borrowed from `text`.
+pub fn begin_match_nfc<'i>(text: &str) -> MatchingPipeline<'i, char> {
+    MatchingPipeline::new(text.nfc())
+}
+
+/// Creates a [MatchingPipeline] over the compatibility composition (NFKC) of `text`, see [begin_match_nfc]
+pub fn begin_match_nfkc<'i>(text: &str) -> MatchingPipeline<'i, char> {
+    MatchingPipeline::new(text.nfkc())
+}
+
+/// Creates a [MatchingPipeline] over the full Unicode case folding of `text`
+/// 
+/// Text that only differs by case then reads the same once patterns are folded
+/// with [fold_case](comparators::fold_case), like `straße` and `STRASSE`.
+/// Offsets count the folded chars, but the matched text and the captures
+/// are slices of `text`, where a char folded into several ones is matched whole.
+pub fn begin_match_case_folded(text: &str) -> MatchingPipeline<'_, char> {
+    let (symbols, origins): (Vec<char>, Vec<usize>) = text.char_indices()
+        .flat_map(|(i, c)| once(c).default_case_fold().map(move |folded| (folded, i)))
+        .unzip();
+
+    MatchingPipeline::from_source(Source::Folded(text, symbols.into(), origins.into()))
+}
+
 /// A convenient way to tell if a pattern match a pipeline or not
 /// while delegating the error handling in a function
-pub trait MatchAgainst<'a, S:Symbol+'a, F> : Clone
-where F: Fn(MatchingPipeline<S>) -> Result<TerminatedPipeline<S>, PipelineError<'a, S>>
+pub trait MatchAgainst<'i, 'a, S:Symbol+'i+'a, P> : Clone
+where P: Pattern<'i, 'a, S>
 {
     /// Matches a pattern against a pipeline
     /// 
     /// This was meant to relieve the immediate code of the error handling of the pipeline.
-    /// It is not recommended to use an anonymous function for callback.
+    /// It is not recommended to use an anonymous function for pattern.
     /// 
     /// Returns Some([TerminatedPipeline]) if successful
     /// 
     /// Returns None if not
-    fn match_against(&self, callback: F) -> Option<TerminatedPipeline<S>>;
+    /// 
+    /// `pattern` can be any [Pattern], and the pipeline is terminated once it matches.
+    /// Functions used to return the [TerminatedPipeline] themselves,
+    /// they now return the [MatchingPipeline] instead:
+    /// 
+    /// ```
+    /// use pattern_matcher::{MatchAgainst, MatchingPipeline, PipelineResult};
+    /// 
+    /// fn version(p: MatchingPipeline<'_, char>) -> PipelineResult<'_, 'static, char> {
+    ///     Ok(p.expect_symbol(&'v')?.match_while_true(char::is_ascii_digit))
+    /// }
+    /// 
+    /// assert_eq!("v12".match_against(version).unwrap().offset(), 3);
+    /// ```
+    fn match_against(&self, pattern: P) -> Option<TerminatedPipeline<'i, S>>;
 }
 
-impl<'a, S:Symbol+'a, F, T:Clone> MatchAgainst<'a, S, F> for T
-where F: Fn(MatchingPipeline<S>) -> Result<TerminatedPipeline<S>, PipelineError<'a, S>>,
-T: Matchable<S>
+impl<'i, 'a, S:Symbol+'i+'a, P, T:Clone> MatchAgainst<'i, 'a, S, P> for T
+where P: Pattern<'i, 'a, S>,
+T: Matchable<'i, S>
 {
-    fn match_against(&self, callback: F) -> Option<TerminatedPipeline<S>> {
-        if let Ok(pipeline) = callback(begin_match(self.clone())){
-            return Some(pipeline)
-        }
-        None
+    fn match_against(&self, pattern: P) -> Option<TerminatedPipeline<'i, S>> {
+        pattern.apply(begin_match(self.clone())).ok().map(MatchingPipeline::terminate)
     }
-}
\ No newline at end of file
+}
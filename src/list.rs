@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+/// A persistent list: cloning it is O(1) and pushing an item
+/// does not affect the clones, so pipelines can be freely copied for backtracking.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct List<T>(Option<Rc<Node<T>>>);
+
+#[derive(Debug, PartialEq)]
+struct Node<T>{
+    item: T,
+    previous: Option<Rc<Node<T>>>
+}
+
+impl<T> Default for List<T>{
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T:Clone> List<T>{
+    pub(crate) fn push(&mut self, item: T){
+        let previous = self.0.take();
+        self.0 = Some(Rc::new(Node{ item, previous }));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Returns the items in the order they were pushed
+    pub(crate) fn items(&self) -> Vec<T> {
+        let mut items = vec![];
+        let mut node = self.0.as_deref();
+
+        while let Some(n) = node {
+            items.push(n.item.clone());
+            node = n.previous.as_deref();
+        }
+
+        items.reverse();
+        items
+    }
+}
+
+impl<T> Drop for List<T>{
+    // Unlinks the list iteratively so long lists don't overflow the stack
+    fn drop(&mut self) {
+        let mut node = self.0.take();
+
+        while let Some(n) = node {
+            node = match Rc::try_unwrap(n) {
+                Ok(mut n) => n.previous.take(),
+                Err(_) => None
+            };
+        }
+    }
+}
@@ -0,0 +1,255 @@
+/// Builds a [Pattern](crate::Pattern) from a concise grammar
+///
+/// ```
+/// use pattern_matcher::{begin_match, pattern};
+///
+/// let number = pattern!(value @ ("0x" [hex]+ | [digit]+) ([ws]* "," [ws]*)?);
+/// let result = begin_match("0x1F, 2").expect(number).unwrap().terminate();
+///
+/// assert_eq!(result.get("value").unwrap().as_str(), "0x1F");
+/// ```
+///
+/// A pattern is a sequence of items separated by spaces, with alternatives separated by `|`.
+/// An item is one of:
+/// * a literal, like `"0x"`, `'a'`, `b'\n'` or `b"ELF"`
+/// * a built-in class from [classes](crate::classes) between brackets:
+///   `[digit]`, `[hex]`, `[alpha]`, `[alnum]`, `[punct]`, `[ws]`, `[letter]`, `[number]` or `[word]`
+/// * a range of symbols between brackets, like `['a'..='z']`
+/// * a group between parentheses
+/// * the name of any other pattern in scope
+///
+/// Each item may be followed by a quantifier, `?`, `*`, `+`, `{n}`, `{m,}` or `{m, n}`,
+/// and preceded by `name @` to record it as a capture.
+///
+/// # Compile errors
+///
+/// Malformed grammars fail to compile with a message pointing at the mistake:
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // A pattern cannot be empty
+/// let _ = pattern!();
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // Expected something to capture after `@`
+/// let _ = pattern!(x @);
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // Expected a class name or a range, like `[digit]` or `['a'..='z']`
+/// let _ = pattern!(['a'..'z']);
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // Unknown class `foo`
+/// let _ = pattern!([foo]);
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // A group cannot be empty
+/// let _ = pattern!("a" ());
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // An alternative cannot be empty
+/// let _ = pattern!("a" | | "b");
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // An alternative cannot be empty
+/// let _ = pattern!("a" |);
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // Expected something to repeat before `?`
+/// let _ = pattern!(? "a");
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // Expected something to repeat before `*`
+/// let _ = pattern!(* "a");
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // Expected something to repeat before `+`
+/// let _ = pattern!(("a") ++);
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // Expected something to repeat before `{3}`
+/// let _ = pattern!({3} "a");
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // Expected `{n}`, `{min,}` or `{min, max}` but found `{x}`
+/// let _ = pattern!("a"{x});
+/// ```
+///
+/// ```compile_fail,E0080
+/// # use pattern_matcher::pattern;
+/// // The minimum of a repetition exceeds its maximum
+/// let _ = pattern!("a"{3, 1});
+/// ```
+///
+/// ```compile_fail
+/// # use pattern_matcher::pattern;
+/// // Unexpected `;` in pattern
+/// let _ = pattern!("a" ; "b");
+/// ```
+#[macro_export]
+macro_rules! pattern {
+    () => {
+        compile_error!("A pattern cannot be empty")
+    };
+    ($($tokens:tt)+) => {
+        $crate::__pattern!(@alt [] [] $($tokens)+)
+    };
+}
+
+/// Implementation of [pattern!], munching tokens one item at a time
+///
+/// * `@alt [branches] [items] tokens` - Reads the items of the current branch
+/// * `@atom [branches] [items] [name] tokens` - Reads an item
+/// * `@quant [branches] [items] [name] (item) tokens` - Reads the quantifier of an item
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __pattern {
+    (@alt [$($branches:tt)*] [$($items:tt)*]) => {
+        $crate::__pattern!(@branches $($branches)* [$($items)*])
+    };
+    (@alt [$($branches:tt)*] [$($items:tt)*] | $($rest:tt)*) => {
+        $crate::__pattern!(@alt [$($branches)* [$($items)*]] [] $($rest)*)
+    };
+    (@alt $branches:tt $items:tt $name:ident @ $($rest:tt)*) => {
+        $crate::__pattern!(@atom $branches $items [$name] $($rest)*)
+    };
+    (@alt $branches:tt $items:tt $($rest:tt)+) => {
+        $crate::__pattern!(@atom $branches $items [] $($rest)+)
+    };
+
+    (@atom $branches:tt $items:tt $name:tt) => {
+        compile_error!("Expected something to capture after `@`")
+    };
+    (@atom $branches:tt $items:tt $name:tt $literal:literal $($rest:tt)*) => {
+        $crate::__pattern!(@quant $branches $items $name ($literal) $($rest)*)
+    };
+    (@atom $branches:tt $items:tt $name:tt [$start:literal ..= $end:literal] $($rest:tt)*) => {
+        $crate::__pattern!(@quant $branches $items $name ($start..=$end) $($rest)*)
+    };
+    (@atom $branches:tt $items:tt $name:tt [$class:ident] $($rest:tt)*) => {
+        $crate::__pattern!(@quant $branches $items $name ($crate::__pattern!(@class $class)) $($rest)*)
+    };
+    (@atom $branches:tt $items:tt $name:tt [$($class:tt)*] $($rest:tt)*) => {
+        compile_error!(concat!("Expected a class name or a range, like `[digit]` or `['a'..='z']`, but found `[", stringify!($($class)*), "]`"))
+    };
+    (@atom $branches:tt $items:tt $name:tt () $($rest:tt)*) => {
+        compile_error!("A group cannot be empty")
+    };
+    (@atom $branches:tt $items:tt $name:tt ($($group:tt)+) $($rest:tt)*) => {
+        $crate::__pattern!(@quant $branches $items $name ($crate::__pattern!(@alt [] [] $($group)+)) $($rest)*)
+    };
+    (@atom $branches:tt $items:tt $name:tt $pattern:ident $($rest:tt)*) => {
+        $crate::__pattern!(@quant $branches $items $name ($pattern) $($rest)*)
+    };
+    (@atom $branches:tt $items:tt $name:tt | $($rest:tt)*) => {
+        compile_error!("An alternative cannot be empty")
+    };
+    (@atom $branches:tt $items:tt $name:tt ? $($rest:tt)*) => {
+        compile_error!("Expected something to repeat before `?`")
+    };
+    (@atom $branches:tt $items:tt $name:tt * $($rest:tt)*) => {
+        compile_error!("Expected something to repeat before `*`")
+    };
+    (@atom $branches:tt $items:tt $name:tt + $($rest:tt)*) => {
+        compile_error!("Expected something to repeat before `+`")
+    };
+    (@atom $branches:tt $items:tt $name:tt {$($bounds:tt)*} $($rest:tt)*) => {
+        compile_error!(concat!("Expected something to repeat before `{", stringify!($($bounds)*), "}`"))
+    };
+    (@atom $branches:tt $items:tt $name:tt $unexpected:tt $($rest:tt)*) => {
+        compile_error!(concat!("Unexpected `", stringify!($unexpected), "` in pattern"))
+    };
+
+    (@quant $branches:tt $items:tt $name:tt $item:tt ? $($rest:tt)*) => {
+        $crate::__pattern!(@repeat $branches $items $name $item ($crate::ZeroOrOne) $($rest)*)
+    };
+    (@quant $branches:tt $items:tt $name:tt $item:tt * $($rest:tt)*) => {
+        $crate::__pattern!(@repeat $branches $items $name $item ($crate::ZeroOrMore) $($rest)*)
+    };
+    (@quant $branches:tt $items:tt $name:tt $item:tt + $($rest:tt)*) => {
+        $crate::__pattern!(@repeat $branches $items $name $item ($crate::AtLeast(1)) $($rest)*)
+    };
+    (@quant $branches:tt $items:tt $name:tt $item:tt {$n:literal} $($rest:tt)*) => {
+        $crate::__pattern!(@repeat $branches $items $name $item ($crate::Between { min: $n, max: $n }) $($rest)*)
+    };
+    (@quant $branches:tt $items:tt $name:tt $item:tt {$min:literal,} $($rest:tt)*) => {
+        $crate::__pattern!(@repeat $branches $items $name $item ($crate::AtLeast($min)) $($rest)*)
+    };
+    (@quant $branches:tt $items:tt $name:tt $item:tt {$min:literal, $max:literal} $($rest:tt)*) => {
+        $crate::__pattern!(@repeat $branches $items $name $item ({
+            const { assert!($min <= $max, "The minimum of a repetition exceeds its maximum") };
+            $crate::Between { min: $min, max: $max }
+        }) $($rest)*)
+    };
+    (@quant $branches:tt $items:tt $name:tt $item:tt {$($bounds:tt)*} $($rest:tt)*) => {
+        compile_error!(concat!("Expected `{n}`, `{min,}` or `{min, max}` but found `{", stringify!($($bounds)*), "}`"))
+    };
+    (@quant $branches:tt [$($items:tt)*] $name:tt $item:tt $($rest:tt)*) => {
+        $crate::__pattern!(@alt $branches [$($items)* ($crate::__pattern!(@named $name $item))] $($rest)*)
+    };
+
+    (@repeat $branches:tt [$($items:tt)*] $name:tt ($($item:tt)*) ($($quantifier:tt)*) $($rest:tt)*) => {
+        $crate::__pattern!(@alt $branches [$($items)* ($crate::__pattern!(@named $name (
+            $crate::Repeat { quantifier: $($quantifier)*, pattern: $($item)* }
+        )))] $($rest)*)
+    };
+
+    (@named [] ($($item:tt)*)) => {
+        $($item)*
+    };
+    (@named [$name:ident] ($($item:tt)*)) => {
+        $crate::Named { name: stringify!($name), pattern: $($item)* }
+    };
+
+    (@branches [$($items:tt)*]) => {
+        $crate::__pattern!(@seq $($items)*)
+    };
+    (@branches [$($items:tt)*] $($rest:tt)+) => {
+        $crate::Alt($crate::__pattern!(@seq $($items)*), $crate::__pattern!(@branches $($rest)+))
+    };
+
+    (@seq) => {
+        compile_error!("An alternative cannot be empty")
+    };
+    (@seq ($($item:tt)*)) => {
+        $($item)*
+    };
+    (@seq ($($item:tt)*) $($rest:tt)+) => {
+        $crate::Seq($($item)*, $crate::__pattern!(@seq $($rest)+))
+    };
+
+    (@class digit) => { $crate::classes::ASCII_DIGIT };
+    (@class hex) => { $crate::classes::HEX_DIGIT };
+    (@class alpha) => { $crate::classes::ASCII_ALPHA };
+    (@class alnum) => { $crate::classes::ASCII_ALNUM };
+    (@class punct) => { $crate::classes::ASCII_PUNCTUATION };
+    (@class ws) => { $crate::classes::WHITESPACE };
+    (@class letter) => { $crate::classes::LETTER };
+    (@class number) => { $crate::classes::NUMBER };
+    (@class word) => { $crate::classes::WORD };
+    (@class $unknown:ident) => {
+        compile_error!(concat!("Unknown class `", stringify!($unknown), "`"))
+    };
+}
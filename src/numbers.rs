@@ -0,0 +1,58 @@
+use crate::{AtLeast, MatchingPipeline, PipelineResult, WithQuantifier, ZeroOrOne};
+
+const DIGITS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+const SIGNS: &[char] = &['+', '-'];
+
+impl<'i, 'a> MatchingPipeline<'i, char>{
+    /// Expects a floating point literal, see [FloatDigester](crate::FloatDigester)
+    /// 
+    /// It is made of an optional sign followed by either:
+    /// * a decimal number with an optional exponent, like `3.14`, `.5`, `1.` or `-3.14e-10`
+    /// * `inf`, `infinity` or `nan`, in any case
+    pub fn expect_float_literal(self) -> PipelineResult<'i, 'a, char> {
+        self.with_quantifier(ZeroOrOne, |p| p.expect_any_of(SIGNS))?
+        .one_of([
+            &|p| p.block(decimal)?.with_quantifier(ZeroOrOne, exponent),
+            &|p| p.block(infinity),
+            &|p| p.block(nan)
+        ])
+    }
+}
+
+/// Digits, with an optional fraction, or a fraction alone
+fn decimal<'i, 'a>(p: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+    p.one_of([
+        &|p| p.with_quantifier(AtLeast(1), |p| p.expect_any_of(DIGITS))?
+            .with_quantifier(ZeroOrOne, |p| {
+                p.expect_symbol(&'.')?
+                .with_quantifier(AtLeast(0), |p| p.expect_any_of(DIGITS))
+            }),
+        &|p| p.expect_symbol(&'.')?
+            .with_quantifier(AtLeast(1), |p| p.expect_any_of(DIGITS))
+    ])
+}
+
+fn exponent<'i, 'a>(p: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+    p.expect_any_of(&['e', 'E'])?
+    .with_quantifier(ZeroOrOne, |p| p.expect_any_of(SIGNS))?
+    .with_quantifier(AtLeast(1), |p| p.expect_any_of(DIGITS))
+}
+
+fn infinity<'i, 'a>(p: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+    p.expect_any_of(&['i', 'I'])?
+    .expect_any_of(&['n', 'N'])?
+    .expect_any_of(&['f', 'F'])?
+    .with_quantifier(ZeroOrOne, |p| {
+        p.expect_any_of(&['i', 'I'])?
+        .expect_any_of(&['n', 'N'])?
+        .expect_any_of(&['i', 'I'])?
+        .expect_any_of(&['t', 'T'])?
+        .expect_any_of(&['y', 'Y'])
+    })
+}
+
+fn nan<'i, 'a>(p: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+    p.expect_any_of(&['n', 'N'])?
+    .expect_any_of(&['a', 'A'])?
+    .expect_any_of(&['n', 'N'])
+}
@@ -0,0 +1,203 @@
+use std::{borrow::Cow, ops::{Add, BitOr, RangeInclusive}};
+
+use crate::{
+    classes::CharClass, MatchError, MatchingPipeline, PipelineError, PipelineResult, Quantifier, Regex, Symbol,
+    WithQuantifier
+};
+
+/// Anything that can be matched from the current position of a [MatchingPipeline]
+///
+/// Patterns are values: they can be stored, named, reused
+/// and combined with [Seq], [Alt], [Repeat] and [Named], with the operators `+` and `|`
+/// or with the [pattern!](crate::pattern!) macro.
+///
+/// It is implemented for:
+/// * closures and functions taking a pipeline and returning a [PipelineResult]
+/// * a single `char` or `u8`, a slice or array of symbols and `&str`, matched literally
+/// * a range of symbols, like `'a'..='z'`
+/// * [CharClass] and [Regex]
+///
+/// Patterns other than closures fail with a [PipelineError].
+/// To combine them with patterns failing with a user error, see [MatchError], wrap them in a closure.
+pub trait Pattern<'i, 'a, S:Symbol+'i, E = PipelineError<'a, S>> {
+    /// Matches the pattern from the current position of `pipeline`
+    fn apply(&self, pipeline: MatchingPipeline<'i, S>) -> PipelineResult<'i, 'a, S, E>;
+}
+
+/// Combinator methods, available on every pattern that is not a closure
+pub trait Combine: Sized {
+    /// Matches `self` then `next`
+    fn then<P>(self, next: P) -> Seq<Self, P> {
+        Seq(self, next)
+    }
+
+    /// Matches `self`, or `other` if `self` does not match
+    fn or<P>(self, other: P) -> Alt<Self, P> {
+        Alt(self, other)
+    }
+
+    /// Matches `self` as many times as `quantifier` allows
+    fn quantified<Q:Quantifier>(self, quantifier: Q) -> Repeat<Q, Self> {
+        Repeat { quantifier, pattern: self }
+    }
+
+    /// Records what `self` matches under `name`
+    fn named(self, name: &'static str) -> Named<Self> {
+        Named { name, pattern: self }
+    }
+}
+
+/// Matches a pattern then another one
+#[derive(Debug, Clone, Copy)]
+pub struct Seq<A, B>(pub A, pub B);
+
+/// Matches a pattern or, if it does not match, another one, see [one_of](MatchingPipeline::one_of)
+#[derive(Debug, Clone, Copy)]
+pub struct Alt<A, B>(pub A, pub B);
+
+/// Records what a pattern matches as a capture, see [capture](MatchingPipeline::capture)
+#[derive(Debug, Clone, Copy)]
+pub struct Named<P>{
+    pub name: &'static str,
+    pub pattern: P
+}
+
+/// Matches a pattern with a [Quantifier]
+/// 
+/// Matching panics if the minimum of the quantifier exceeds its maximum, see [Between](crate::Between)
+#[derive(Debug, Clone, Copy)]
+pub struct Repeat<Q:Quantifier, P>{
+    pub quantifier: Q,
+    pub pattern: P
+}
+
+impl<'i, 'a, S:Symbol+'i, E, F> Pattern<'i, 'a, S, E> for F
+where F: Fn(MatchingPipeline<'i, S>) -> PipelineResult<'i, 'a, S, E>
+{
+    fn apply(&self, pipeline: MatchingPipeline<'i, S>) -> PipelineResult<'i, 'a, S, E> {
+        self(pipeline)
+    }
+}
+
+impl<'i, 'a> Pattern<'i, 'a, char> for char {
+    fn apply(&self, pipeline: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+        pipeline.expect_symbol_cow(Cow::Owned(*self))
+    }
+}
+
+impl<'i, 'a> Pattern<'i, 'a, u8> for u8 {
+    fn apply(&self, pipeline: MatchingPipeline<'i, u8>) -> PipelineResult<'i, 'a, u8> {
+        pipeline.expect_symbol_cow(Cow::Owned(*self))
+    }
+}
+
+impl<'i, 'a, S:Symbol+'i+'a> Pattern<'i, 'a, S> for &'a [S] {
+    fn apply(&self, pipeline: MatchingPipeline<'i, S>) -> PipelineResult<'i, 'a, S> {
+        pipeline.expect_pattern(self)
+    }
+}
+
+impl<'i, 'a, S:Symbol+'i+'a, const N: usize> Pattern<'i, 'a, S> for &'a [S; N] {
+    fn apply(&self, pipeline: MatchingPipeline<'i, S>) -> PipelineResult<'i, 'a, S> {
+        pipeline.expect_pattern(*self)
+    }
+}
+
+impl<'i, 'a> Pattern<'i, 'a, char> for &str {
+    fn apply(&self, mut pipeline: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+        match pipeline.find_pattern(self.chars()) {
+            Some((end, symbols)) => {
+                pipeline.advance(end - pipeline.pos, symbols);
+                Ok(pipeline)
+            },
+            // The expected chars are only collected to report the failure
+            None => pipeline.expect_pattern_cow(Cow::Owned(self.chars().collect()))
+        }
+    }
+}
+
+impl<'i, 'a, S:Symbol+PartialOrd+'i+'a> Pattern<'i, 'a, S> for RangeInclusive<S> {
+    fn apply(&self, pipeline: MatchingPipeline<'i, S>) -> PipelineResult<'i, 'a, S> {
+        pipeline.expect_range(self.clone())
+    }
+}
+
+impl<'i, 'a> Pattern<'i, 'a, char> for CharClass {
+    fn apply(&self, pipeline: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+        pipeline.expect_class(*self)
+    }
+}
+
+impl<'i, 'a> Pattern<'i, 'a, char> for &'a Regex {
+    fn apply(&self, pipeline: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+        self.run(pipeline)
+    }
+}
+
+impl<'i, 'a, S:Symbol+'i, E, A, B> Pattern<'i, 'a, S, E> for Seq<A, B>
+where A: Pattern<'i, 'a, S, E>, B: Pattern<'i, 'a, S, E>
+{
+    fn apply(&self, pipeline: MatchingPipeline<'i, S>) -> PipelineResult<'i, 'a, S, E> {
+        self.1.apply(self.0.apply(pipeline)?)
+    }
+}
+
+impl<'i, 'a, S:Symbol+'i+'a, E, A, B> Pattern<'i, 'a, S, E> for Alt<A, B>
+where A: Pattern<'i, 'a, S, E>, B: Pattern<'i, 'a, S, E>, E: MatchError<'a, S>
+{
+    fn apply(&self, pipeline: MatchingPipeline<'i, S>) -> PipelineResult<'i, 'a, S, E> {
+        pipeline.one_of([&|p| self.0.apply(p), &|p| self.1.apply(p)])
+    }
+}
+
+impl<'i, 'a, S:Symbol+'i+'a, E, Q, P> Pattern<'i, 'a, S, E> for Repeat<Q, P>
+where Q: Quantifier+Clone, P: Pattern<'i, 'a, S, E>, MatchingPipeline<'i, S>: WithQuantifier<'i, 'a, Q, S>, E: MatchError<'a, S>
+{
+    fn apply(&self, pipeline: MatchingPipeline<'i, S>) -> PipelineResult<'i, 'a, S, E> {
+        pipeline.with_quantifier(self.quantifier.clone(), |p| self.pattern.apply(p))
+    }
+}
+
+impl<'i, 'a, S:Symbol+'i+'a, E, P> Pattern<'i, 'a, S, E> for Named<P>
+where P: Pattern<'i, 'a, S, E>
+{
+    fn apply(&self, pipeline: MatchingPipeline<'i, S>) -> PipelineResult<'i, 'a, S, E> {
+        pipeline.capture(self.name, |p| self.pattern.apply(p))
+    }
+}
+
+impl Combine for char{}
+impl Combine for u8{}
+impl<S> Combine for &[S]{}
+impl<S, const N: usize> Combine for &[S; N]{}
+impl Combine for &str{}
+impl<S> Combine for RangeInclusive<S>{}
+impl Combine for CharClass{}
+impl Combine for &Regex{}
+impl<A, B> Combine for Seq<A, B>{}
+impl<A, B> Combine for Alt<A, B>{}
+impl<Q:Quantifier, P> Combine for Repeat<Q, P>{}
+impl<P> Combine for Named<P>{}
+
+/// Implements `+` as [Seq] and `|` as [Alt] for a combinable type
+macro_rules! operators {
+    ($([$($generics:tt)*] $type:ty),*) => {$(
+        impl<$($generics)* Rhs> Add<Rhs> for $type {
+            type Output = Seq<Self, Rhs>;
+
+            fn add(self, rhs: Rhs) -> Self::Output {
+                Seq(self, rhs)
+            }
+        }
+
+        impl<$($generics)* Rhs> BitOr<Rhs> for $type {
+            type Output = Alt<Self, Rhs>;
+
+            fn bitor(self, rhs: Rhs) -> Self::Output {
+                Alt(self, rhs)
+            }
+        }
+    )*};
+}
+
+operators!([] CharClass, [A, B,] Seq<A, B>, [A, B,] Alt<A, B>, [Q:Quantifier, P,] Repeat<Q, P>, [P,] Named<P>);
@@ -0,0 +1,61 @@
+use std::{fmt::Display, rc::Rc};
+
+/// Where a pipeline stands in its input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Position{
+    /// Number of symbols read before this position
+    pub offset: usize,
+
+    /// Line and column, when the pipeline [tracks lines](crate::MatchingPipeline::track_lines)
+    pub line_column: Option<LineColumn>
+}
+
+/// A line and a column, both starting at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineColumn{
+    pub line: usize,
+    pub column: usize
+}
+
+impl Position{
+    /// A position with no line information
+    pub fn at(offset: usize) -> Self {
+        Self { offset, line_column: None }
+    }
+}
+
+impl Display for Position{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line_column {
+            Some(LineColumn { line, column }) => write!(f, "line {line}, column {column}"),
+            None => write!(f, "offset {}", self.offset)
+        }
+    }
+}
+
+/// Counts the lines read by a pipeline
+#[derive(Debug, Clone)]
+pub(crate) struct LineTracker<S>{
+    newline: Rc<S>,
+    line: usize,
+    /// Offset of the first symbol of the current line
+    line_start: usize
+}
+
+impl<S:PartialEq> LineTracker<S>{
+    pub(crate) fn new(newline: S) -> Self {
+        Self { newline: Rc::new(newline), line: 1, line_start: 0 }
+    }
+
+    /// Takes note of `symbol`, the symbol right before `offset`
+    pub(crate) fn track(&mut self, symbol: &S, offset: usize) {
+        if *symbol == *self.newline {
+            self.line += 1;
+            self.line_start = offset;
+        }
+    }
+
+    pub(crate) fn line_column(&self, offset: usize) -> LineColumn {
+        LineColumn { line: self.line, column: offset - self.line_start + 1 }
+    }
+}
@@ -1,138 +1,277 @@
-use std::num::NonZeroUsize;
+use std::{num::NonZeroUsize, ops::RangeInclusive};
 
-use crate::{MatchingPipeline, PipelineResult, Symbol};
+use crate::{MatchError, MatchingPipeline, PipelineResult, Symbol};
 
 pub trait Quantifier{}
 
+#[derive(Debug, Clone, Copy)]
 pub struct Exactly(pub NonZeroUsize); impl Quantifier for Exactly{}
+#[derive(Debug, Clone, Copy)]
 pub struct ZeroOrOne; impl Quantifier for ZeroOrOne{}
+#[derive(Debug, Clone, Copy)]
 pub struct AtLeast(pub usize); impl Quantifier for AtLeast{}
+#[derive(Debug, Clone, Copy)]
 pub struct AtMost(pub NonZeroUsize); impl Quantifier for AtMost{}
+#[derive(Debug, Clone, Copy)]
 pub struct ZeroOrMore; impl Quantifier for ZeroOrMore{}
 
-pub trait WithQuantifier<'a, Q:Quantifier, S:Symbol> {
-    fn with_quantifier<F>(self, quantifier:Q, callback: F) -> PipelineResult<'a, S> where F: Fn(Self) -> PipelineResult<'a, S>, Self: Sized;
+/// Matches from `min` to `max` times, both included
+/// 
+/// Every other quantifier is a special case of this one.
+/// Repetition stops after an iteration that matches nothing,
+/// since all the following ones would match nothing as well.
+/// A `RangeInclusive<usize>` can be used in its place.
+/// 
+/// # Panics
+/// 
+/// Quantifying with `min` greater than `max`, or with an empty range like `3..=2`, panics
+/// rather than silently matching nothing.
+#[derive(Debug, Clone, Copy)]
+pub struct Between{ pub min: usize, pub max: usize } impl Quantifier for Between{}
+
+impl Quantifier for RangeInclusive<usize>{}
+
+impl From<RangeInclusive<usize>> for Between{
+    fn from(value: RangeInclusive<usize>) -> Self {
+        Self { min: *value.start(), max: *value.end() }
+    }
 }
 
-impl<'a, S:Symbol> WithQuantifier<'a, Exactly, S> for MatchingPipeline<S>  {
-    fn with_quantifier<F>(mut self, quantifier:Exactly, callback: F) -> PipelineResult<'a, S> where F: Fn(Self) -> PipelineResult<'a, S> {
+impl From<Exactly> for Between{
+    fn from(value: Exactly) -> Self {
+        Self { min: value.0.get(), max: value.0.get() }
+    }
+}
 
-        let mut n:Option<NonZeroUsize> = None;
-        loop{
+impl From<ZeroOrOne> for Between{
+    fn from(_: ZeroOrOne) -> Self {
+        Self { min: 0, max: 1 }
+    }
+}
 
-            let pipeline = self.clone();
+impl From<AtLeast> for Between{
+    fn from(value: AtLeast) -> Self {
+        Self { min: value.0, max: usize::MAX }
+    }
+}
 
-            let result = pipeline.block(&callback);
+impl From<AtMost> for Between{
+    fn from(value: AtMost) -> Self {
+        Self { min: 0, max: value.0.get() }
+    }
+}
 
-            if let Ok(p) = result {
-                self = p;
+impl From<ZeroOrMore> for Between{
+    fn from(_: ZeroOrMore) -> Self {
+        Self { min: 0, max: usize::MAX }
+    }
+}
 
-                if n.is_none() {
-                    n = Some(NonZeroUsize::new(1).unwrap())
-                }else{
-                    n = n.and_then(|x| x.checked_add(1));
-                }
-                
-                // On est sûr que n vaut Some(...)
-                let n = n.unwrap();
-
-                if n == quantifier.0 {
-                    return Ok(self);
-                }else if n < quantifier.0 {
-                    continue;
-                }
+/// How a quantifier followed by a continuation picks its number of iterations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuantifierMode{
+    /// As many iterations as possible, giving some back until the continuation matches
+    Greedy,
 
-            }else{
-                return result;
-            }
+    /// As few iterations as possible, adding more until the continuation matches
+    Lazy,
 
-            
+    /// As many iterations as possible, never giving any back
+    Possessive
+}
 
-        }
+impl Between{
+    fn checked(self) -> Self {
+        assert!(self.min <= self.max, "The minimum ({}) of a quantifier exceeds its maximum ({})", self.min, self.max);
+        self
+    }
+}
 
-        
+/// Returns the error of a failed attempt, unless it is a user error, see [MatchError]
+/// 
+/// A failed iteration or continuation only ends a repetition when it did not match,
+/// user errors are passed on right away as `Err`
+fn ordinary<'a, S:Symbol+'a, E:MatchError<'a, S>>(error: E) -> Result<E, E> {
+    match error.into_pipeline_error() {
+        Ok(error) => Ok(error.into()),
+        Err(error) => Err(error)
     }
 }
 
-impl<'a, S:Symbol> WithQuantifier<'a, ZeroOrOne, S> for MatchingPipeline<S> {
-    fn with_quantifier<F>(mut self, _:ZeroOrOne, callback: F) -> PipelineResult<'a, S> where F: Fn(Self) -> PipelineResult<'a, S>, Self: Sized {
-        
-        let p = self.clone();
-        if let Ok(pipeline) = p.block(callback){
-            self = pipeline;
+pub trait WithQuantifier<'i, 'a, Q:Quantifier, S:Symbol> {
+    /// Runs `callback` as many times as `quantifier` allows
+    /// 
+    /// Repetition stops at the first iteration that does not match,
+    /// but a user error from `callback` is passed on, see [MatchError].
+    /// 
+    /// # Panics
+    /// 
+    /// If the minimum of the quantifier exceeds its maximum, see [Between]
+    fn with_quantifier<F, E>(self, quantifier:Q, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>, S: 'a, Self: Sized;
+}
+
+impl<'i, 'a, S:Symbol> WithQuantifier<'i, 'a, Between, S> for MatchingPipeline<'i, S> {
+    fn with_quantifier<F, E>(mut self, quantifier:Between, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>, S: 'a {
+        let Between { min, max } = quantifier.checked();
+
+        let mut n = 0;
+        while n < max {
+            match self.clone().block(&callback) {
+                Ok(p) if p.offset == self.offset => return Ok(p),
+                Ok(p) => {
+                    self = p;
+                    n += 1;
+                },
+                Err(error) if n < min => return Err(error),
+                Err(error) => {
+                    ordinary(error)?;
+                    break;
+                }
+            }
         }
 
         Ok(self)
     }
 }
 
-impl<'a, S:Symbol> WithQuantifier<'a, AtLeast, S> for MatchingPipeline<S> {
-    fn with_quantifier<F>(mut self, quantifier:AtLeast, callback: F) -> PipelineResult<'a, S> where F: Fn(Self) -> PipelineResult<'a, S>, Self: Sized {
-        let mut n = 0;
-        loop {
+impl<'i, 'a, S:Symbol> WithQuantifier<'i, 'a, RangeInclusive<usize>, S> for MatchingPipeline<'i, S> {
+    fn with_quantifier<F, E>(self, quantifier:RangeInclusive<usize>, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>, S: 'a {
+        self.with_quantifier(Between::from(quantifier), callback)
+    }
+}
+
+impl<'i, 'a, S:Symbol> WithQuantifier<'i, 'a, Exactly, S> for MatchingPipeline<'i, S>  {
+    fn with_quantifier<F, E>(self, quantifier:Exactly, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>, S: 'a {
+        self.with_quantifier(Between::from(quantifier), callback)
+    }
+}
 
-            let pipeline = self.clone();
-            let result = pipeline.block(&callback);
+impl<'i, 'a, S:Symbol> WithQuantifier<'i, 'a, ZeroOrOne, S> for MatchingPipeline<'i, S> {
+    fn with_quantifier<F, E>(self, quantifier:ZeroOrOne, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>, S: 'a, Self: Sized {
+        self.with_quantifier(Between::from(quantifier), callback)
+    }
+}
 
-            if let Ok(p) = result {
-                self = p;
+impl<'i, 'a, S:Symbol> WithQuantifier<'i, 'a, AtLeast, S> for MatchingPipeline<'i, S> {
+    fn with_quantifier<F, E>(self, quantifier:AtLeast, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>, S: 'a, Self: Sized {
+        self.with_quantifier(Between::from(quantifier), callback)
+    }
+}
 
-                n += 1;
-            }else if n >= quantifier.0{
-                return Ok(self);
-            }else {
-                return result;
-            }
+impl<'i, 'a, S:Symbol> WithQuantifier<'i, 'a, AtMost, S> for MatchingPipeline<'i, S> {
+    fn with_quantifier<F, E>(self, quantifier:AtMost, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>, S: 'a, Self: Sized {
+        self.with_quantifier(Between::from(quantifier), callback)
+    }
+}
+
+impl<'i, 'a, S:Symbol> WithQuantifier<'i, 'a, ZeroOrMore, S> for MatchingPipeline<'i, S>{
+    fn with_quantifier<F, E>(self, quantifier:ZeroOrMore, callback: F) -> PipelineResult<'i, 'a, S, E> where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>, S: 'a, Self: Sized {
+        self.with_quantifier(Between::from(quantifier), callback)
+    }
+}
+
+/// Repeats a callback, then matches the rest of the pattern with a continuation
+/// 
+/// Unlike [WithQuantifier], the number of iterations depends on the continuation,
+/// as chosen by the [QuantifierMode].
+pub trait WithQuantifierThen<'i, 'a, Q:Quantifier, S:Symbol> {
+    /// Runs `callback` as many times as `quantifier` and `mode` allow, then `continuation`
+    /// 
+    /// A user error from either of them is passed on right away, see [MatchError].
+    /// 
+    /// # Panics
+    /// 
+    /// If the minimum of the quantifier exceeds its maximum, see [Between]
+    fn with_quantifier_then<F, C, E>(self, quantifier:Q, mode: QuantifierMode, callback: F, continuation: C) -> PipelineResult<'i, 'a, S, E>
+    where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, C: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>, S: 'a, Self: Sized;
+}
+
+impl<'i, 'a, Q:Quantifier+Into<Between>, S:Symbol> WithQuantifierThen<'i, 'a, Q, S> for MatchingPipeline<'i, S> {
+    fn with_quantifier_then<F, C, E>(self, quantifier:Q, mode: QuantifierMode, callback: F, continuation: C) -> PipelineResult<'i, 'a, S, E>
+    where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, C: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>, S: 'a
+    {
+        let Between { min, max } = quantifier.into().checked();
+
+        match mode {
+            QuantifierMode::Possessive => self.with_quantifier(Between { min, max }, callback).and_then(continuation),
+            QuantifierMode::Lazy => self.lazy(min, max, callback, continuation),
+            QuantifierMode::Greedy => self.greedy(min, max, callback, continuation)
         }
-        
     }
 }
 
-impl<'a, S:Symbol> WithQuantifier<'a, AtMost, S> for MatchingPipeline<S> {
-    fn with_quantifier<F>(mut self, quantifier:AtMost, callback: F) -> PipelineResult<'a, S> where F: Fn(Self) -> PipelineResult<'a, S>, Self: Sized {
-        
-        let mut  n:Option<NonZeroUsize> = None;
+impl<'i, 'a, S:Symbol+'a> MatchingPipeline<'i, S>{
+    /// Tries the continuation after each iteration, starting from `min` of them
+    fn lazy<F, C, E>(mut self, min: usize, max: usize, callback: F, continuation: C) -> PipelineResult<'i, 'a, S, E>
+    where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, C: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>
+    {
+        let mut n = 0;
+        let mut last_error = None;
 
         loop {
-            let pipeline = self.clone();
-            let result = pipeline.block(&callback);
+            if n >= min {
+                match continuation(self.clone()) {
+                    Ok(p) => return Ok(p),
+                    Err(error) if n == max => return Err(error),
+                    Err(error) => last_error = Some(ordinary(error)?)
+                }
+            }
 
-            if let Ok(p) = result {
-                self = p;
+            match self.clone().block(&callback) {
+                Ok(p) if p.offset == self.offset => match last_error {
+                    // The continuation already failed right here
+                    Some(error) => return Err(error),
+                    None => {
+                        self = p;
+                        n = min;
+                    }
+                },
+                Ok(p) => {
+                    self = p;
+                    n += 1;
+                },
+                Err(error) => return Err(last_error.unwrap_or(ordinary(error)?))
+            }
+        }
+    }
 
-                if n.is_none() {
-                    n = Some(NonZeroUsize::new(1).unwrap())
-                }else{
-                    n = n.and_then(|x| x.checked_add(1));
-                }
-                
-                // On est sûr que n vaut Some(...)
-                let n = n.unwrap();
-
-                if n == quantifier.0 {
-                    return Ok(self);
-                }else if n < quantifier.0 {
-                    continue;
-                }
-            }else{
+    /// Repeats as much as possible, then tries the continuation
+    /// after each iteration from the last one back to `min`
+    fn greedy<F, C, E>(self, mut min: usize, max: usize, callback: F, continuation: C) -> PipelineResult<'i, 'a, S, E>
+    where F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, C: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>
+    {
+        let mut iterations = vec![self];
 
-                if n.is_none(){
-                    return result;
-                }
+        while iterations.len() <= max {
+            let last = &iterations[iterations.len() - 1];
 
-                let n = n.unwrap();
-                if n <= quantifier.0 {
-                    return Ok(self);
+            match last.clone().block(&callback) {
+                Ok(p) if p.offset == last.offset => {
+                    min = min.min(iterations.len() - 1);
+                    break;
+                },
+                Ok(p) => iterations.push(p),
+                Err(error) if iterations.len() <= min => return Err(error),
+                Err(error) => {
+                    ordinary(error)?;
+                    break;
                 }
+            }
+        }
+
+        let mut first_error = None;
+
+        while iterations.len() > min {
+            let p = iterations.pop().expect("There is at least one iteration left");
 
-                return result;
+            match continuation(p) {
+                Ok(p) => return Ok(p),
+                Err(error) => {
+                    first_error.get_or_insert(ordinary(error)?);
+                }
             }
         }
-    }
-}
 
-impl<'a, S:Symbol> WithQuantifier<'a, ZeroOrMore, S> for MatchingPipeline<S>{
-    fn with_quantifier<F>(self, _:ZeroOrMore, callback: F) -> PipelineResult<'a, S> where F: Fn(Self) -> PipelineResult<'a, S>, Self: Sized {
-        self.with_quantifier(AtLeast(0), callback)
+        Err(first_error.expect("The continuation was tried at least once"))
     }
-}
\ No newline at end of file
+}
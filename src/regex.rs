@@ -0,0 +1,742 @@
+use std::{borrow::Cow, error::Error, fmt::Display, ops::Range, rc::Rc};
+
+use crate::{classes::{self, CharClass}, MatchingPipeline, PipelineError, PipelineResult, QuantifierMode, TerminatedPipeline};
+
+/// A pattern written in a subset of the regular expression syntax
+///
+/// It is compiled once and can then run on any number of pipelines:
+/// * literals, escaped with `\` when they are meta characters, and `.` for any char but a newline
+/// * classes like `[a-z_]` or `[^"]`, and `\d`, `\w`, `\s` along with their negations `\D`, `\W`, `\S`
+/// * groups `(…)` and named groups `(?<name>…)`, numbered from 1 in the order they open,
+///   and non capturing groups `(?:…)`
+/// * alternations `a|b`
+/// * quantifiers `?`, `*`, `+`, `{m}`, `{m,}` and `{m,n}`, greedy by default,
+///   lazy when followed by `?`, possessive when followed by `+`
+/// * anchors `^` and `$`, for the start and the end of the input
+///
+/// Groups are recorded as [captures](MatchingPipeline::capture) named after their number,
+/// and named groups under their name as well.
+/// Literals are compared with the [comparator](MatchingPipeline::with_comparator) of the pipeline.
+///
+/// Matching backtracks, so some patterns take exponential time on some inputs
+#[derive(Debug, Clone)]
+pub struct Regex{
+    pattern: String,
+    program: Program
+}
+
+/// A syntax error in a [Regex], see [Diagnostic::for_regex](crate::Diagnostic::for_regex)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexError{
+    pub kind: RegexErrorKind,
+
+    /// The chars of the pattern at fault
+    pub span: Range<usize>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexErrorKind{
+    UnclosedGroup,
+    UnopenedGroup,
+    UnsupportedGroup,
+    InvalidGroupName,
+    DuplicateGroupName(String),
+    UnclosedClass,
+    InvalidClassRange{ start: char, end: char },
+    NothingToRepeat,
+    UnclosedRepetition,
+    InvalidRepetition,
+    UnknownEscape(char),
+    TrailingBackslash
+}
+
+#[derive(Debug, Clone)]
+enum Node{
+    Symbol(char),
+    Class(Class),
+    Start,
+    End,
+    /// A group, capturing under each of its names
+    Group{ names: Vec<Rc<str>>, node: Box<Node> },
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat{ node: Box<Node>, min: usize, max: usize, mode: QuantifierMode }
+}
+
+#[derive(Debug, Clone)]
+struct Class{
+    /// The name in errors, either the name of a built-in class or the class as written
+    name: String,
+    negated: bool,
+    items: Vec<ClassItem>
+}
+
+#[derive(Debug, Clone)]
+enum ClassItem{
+    Range(char, char),
+    Builtin{ class: CharClass, negated: bool }
+}
+
+/// Any char but a newline, for `.`
+const ANY: CharClass = CharClass::new("any character but a newline", |c| *c != '\n');
+
+impl Regex{
+    /// Compiles `pattern`
+    pub fn new(pattern: &str) -> Result<Self, RegexError> {
+        let root = Parser { chars: pattern.chars().collect(), pos: 0, groups: 0, names: vec![] }.parse()?;
+
+        Ok(Self { pattern: pattern.to_string(), program: Program::compile(root) })
+    }
+
+    /// The pattern as written
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Matches the pattern from the current position of `pipeline`
+    ///
+    /// It can be used as a step of a larger pattern, with [block](MatchingPipeline::block) for instance
+    pub fn run<'i, 'a>(&'a self, pipeline: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+        Backtracker {
+            program: &self.program,
+            stack: vec![],
+            groups: vec![None; self.program.groups],
+            loops: vec![Iterations::default(); self.program.loops],
+            atomics: vec![0; self.program.atomics]
+        }.run(pipeline)
+    }
+
+    /// Matches the pattern from the start of `input`
+    pub fn match_str<'i, 'a>(&'a self, input: &'i str) -> Result<TerminatedPipeline<'i, char>, PipelineError<'a, char>> {
+        self.run(MatchingPipeline::from(input)).map(MatchingPipeline::terminate)
+    }
+}
+
+/// The instructions a [Regex] compiles to
+#[derive(Debug, Clone, Default)]
+struct Program{
+    instructions: Vec<Instruction>,
+    /// Number of capturing groups, loops and atomic sections, each with its own slot
+    groups: usize,
+    loops: usize,
+    atomics: usize
+}
+
+#[derive(Debug, Clone)]
+enum Instruction{
+    Symbol(char),
+    Class(Class),
+    Start,
+    End,
+    /// Remembers where the group in the slot starts
+    GroupStart(usize),
+    /// Records what the group in the slot matched under each of its names
+    GroupEnd{ slot: usize, names: Vec<Rc<str>> },
+    /// Continues with the next instruction, then with the given one when backtracking
+    Split(usize),
+    Jump(usize),
+    /// Resets the count of iterations of the loop in the slot
+    LoopStart(usize),
+    /// Runs an iteration, the following instructions, or continues at `exit`
+    Loop{ slot: usize, min: usize, max: usize, lazy: bool, exit: usize },
+    /// Counts an iteration of the loop starting at the given instruction and goes back to it
+    LoopNext(usize),
+    /// Remembers how many backtracking points there are
+    AtomicStart(usize),
+    /// Drops the backtracking points added since the matching [Instruction::AtomicStart]
+    AtomicEnd(usize),
+    Match
+}
+
+impl Program{
+    fn compile(root: Node) -> Self {
+        let mut program = Self::default();
+        program.node(root);
+        program.emit(Instruction::Match);
+
+        program
+    }
+
+    fn emit(&mut self, instruction: Instruction) -> usize {
+        self.instructions.push(instruction);
+        self.instructions.len() - 1
+    }
+
+    /// Points the jump at `at` to the next instruction
+    fn patch(&mut self, at: usize) {
+        let target = self.instructions.len();
+
+        match &mut self.instructions[at] {
+            Instruction::Split(to) | Instruction::Jump(to) | Instruction::Loop { exit: to, .. } => *to = target,
+            instruction => unreachable!("{instruction:?} is not a jump")
+        }
+    }
+
+    fn node(&mut self, node: Node) {
+        match node {
+            Node::Symbol(symbol) => {
+                self.emit(Instruction::Symbol(symbol));
+            },
+            Node::Class(class) => {
+                self.emit(Instruction::Class(class));
+            },
+            Node::Start => {
+                self.emit(Instruction::Start);
+            },
+            Node::End => {
+                self.emit(Instruction::End);
+            },
+            Node::Group { names, node } if names.is_empty() => self.node(*node),
+            Node::Group { names, node } => {
+                let slot = self.groups;
+                self.groups += 1;
+
+                self.emit(Instruction::GroupStart(slot));
+                self.node(*node);
+                self.emit(Instruction::GroupEnd { slot, names });
+            },
+            Node::Concat(nodes) => nodes.into_iter().for_each(|node| self.node(node)),
+            Node::Alternation(mut branches) => {
+                let last = branches.pop().expect("An alternation has branches");
+                let mut jumps = vec![];
+
+                for branch in branches {
+                    let split = self.emit(Instruction::Split(0));
+                    self.node(branch);
+                    jumps.push(self.emit(Instruction::Jump(0)));
+                    self.patch(split);
+                }
+
+                self.node(last);
+                jumps.into_iter().for_each(|jump| self.patch(jump));
+            },
+            Node::Repeat { max: 0, .. } => {},
+            Node::Repeat { node, min, max, mode } => {
+                // A possessive quantifier never gives back what its loop matched
+                let atomic = (mode == QuantifierMode::Possessive).then(|| {
+                    self.atomics += 1;
+                    self.emit(Instruction::AtomicStart(self.atomics - 1));
+                    self.atomics - 1
+                });
+
+                let slot = self.loops;
+                self.loops += 1;
+
+                self.emit(Instruction::LoopStart(slot));
+                let start = self.emit(Instruction::Loop { slot, min, max, lazy: mode == QuantifierMode::Lazy, exit: 0 });
+                self.node(*node);
+                self.emit(Instruction::LoopNext(start));
+                self.patch(start);
+
+                if let Some(slot) = atomic {
+                    self.emit(Instruction::AtomicEnd(slot));
+                }
+            }
+        }
+    }
+}
+
+/// The iterations of a loop so far
+#[derive(Debug, Clone, Copy, Default)]
+struct Iterations{
+    count: usize,
+    /// Offset where the current iteration starts
+    start: usize
+}
+
+/// What to do when backtracking
+enum Frame<'i>{
+    /// Resumes matching at an instruction
+    Retry{ pc: usize, pipeline: MatchingPipeline<'i, char> },
+    /// Restores the state of a slot, as it was when the frame was pushed
+    Group{ slot: usize, start: Option<MatchingPipeline<'i, char>> },
+    Loop{ slot: usize, iterations: Iterations },
+    Atomic{ slot: usize, mark: usize }
+}
+
+/// Runs a [Program], keeping its backtracking points on a stack rather than recursing,
+/// so that long inputs cannot overflow the call stack
+struct Backtracker<'i, 'a>{
+    program: &'a Program,
+    stack: Vec<Frame<'i>>,
+    groups: Vec<Option<MatchingPipeline<'i, char>>>,
+    loops: Vec<Iterations>,
+    /// Size of the stack when each atomic section started
+    atomics: Vec<usize>
+}
+
+impl<'i, 'a> Backtracker<'i, 'a>{
+    /// Tries every backtracking point until one matches, failing with the error that went the furthest
+    fn run(mut self, pipeline: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+        let mut thread = Some((0, pipeline));
+        let mut error = None;
+
+        while let Some((pc, p)) = thread {
+            match self.execute(pc, p) {
+                Ok(p) => return Ok(p),
+                Err(Some(e)) => error = Some(furthest(error, e)),
+                Err(None) => {}
+            }
+
+            thread = self.backtrack();
+        }
+
+        Err(error.expect("Every failed attempt ends with an error"))
+    }
+
+    /// Runs the instructions from `pc` until the program matches or fails
+    ///
+    /// Attempts that fail without an error, like empty iterations, are not worth reporting
+    fn execute(&mut self, mut pc: usize, mut p: MatchingPipeline<'i, char>) -> Result<MatchingPipeline<'i, char>, Option<PipelineError<'a, char>>> {
+        let instructions = &self.program.instructions;
+
+        loop {
+            match &instructions[pc] {
+                Instruction::Symbol(symbol) => p = p.expect_symbol(symbol)?,
+                Instruction::Class(class) => p = class.expect(p)?,
+                Instruction::Start if p.offset() == 0 => {},
+                Instruction::Start => return Err(Some(PipelineError::Unexpected {
+                    message: Cow::Borrowed("Expected the start of the input"),
+                    position: p.position()
+                })),
+                Instruction::End => p = p.expect_eos()?,
+                Instruction::GroupStart(slot) => {
+                    let start = self.groups[*slot].replace(p.clone());
+                    self.stack.push(Frame::Group { slot: *slot, start });
+                },
+                Instruction::GroupEnd { slot, names } => {
+                    let start = self.groups[*slot].as_ref().expect("A group ends after it starts");
+                    p = names.iter().fold(p, |p, name| p.record_capture(name.clone(), start));
+                },
+                Instruction::Split(alternative) => {
+                    self.stack.push(Frame::Retry { pc: *alternative, pipeline: p.clone() });
+                },
+                Instruction::Jump(target) => {
+                    pc = *target;
+                    continue;
+                },
+                Instruction::LoopStart(slot) => {
+                    let iterations = std::mem::replace(&mut self.loops[*slot], Iterations { count: 0, start: p.offset() });
+                    self.stack.push(Frame::Loop { slot: *slot, iterations });
+                },
+                Instruction::Loop { slot, min, max, lazy, exit } => {
+                    let count = self.loops[*slot].count;
+
+                    if count == *max {
+                        pc = *exit;
+                        continue;
+                    }
+
+                    if count >= *min {
+                        let (now, later) = if *lazy { (*exit, pc + 1) } else { (pc + 1, *exit) };
+                        self.stack.push(Frame::Retry { pc: later, pipeline: p.clone() });
+                        pc = now;
+                        continue;
+                    }
+                },
+                Instruction::LoopNext(start) => {
+                    let Instruction::Loop { slot, min, exit, .. } = instructions[*start] else {
+                        unreachable!("A loop ends where it starts");
+                    };
+                    let iterations = self.loops[slot];
+
+                    // Another iteration that matched nothing would not get any further
+                    if p.offset() == iterations.start {
+                        if iterations.count >= min {
+                            return Err(None);
+                        }
+
+                        pc = exit;
+                        continue;
+                    }
+
+                    self.loops[slot] = Iterations { count: iterations.count + 1, start: p.offset() };
+                    self.stack.push(Frame::Loop { slot, iterations });
+                    pc = *start;
+                    continue;
+                },
+                Instruction::AtomicStart(slot) => {
+                    self.stack.push(Frame::Atomic { slot: *slot, mark: self.atomics[*slot] });
+                    self.atomics[*slot] = self.stack.len();
+                },
+                Instruction::AtomicEnd(slot) => {
+                    // The state of the slots must still be restored when backtracking further
+                    let section = self.stack.split_off(self.atomics[*slot]);
+                    self.stack.extend(section.into_iter().filter(|frame| !matches!(frame, Frame::Retry { .. })));
+                },
+                Instruction::Match => return Ok(p)
+            }
+
+            pc += 1;
+        }
+    }
+
+    /// Restores the state up to the latest backtracking point and returns it
+    fn backtrack(&mut self) -> Option<(usize, MatchingPipeline<'i, char>)> {
+        while let Some(frame) = self.stack.pop() {
+            match frame {
+                Frame::Retry { pc, pipeline } => return Some((pc, pipeline)),
+                Frame::Group { slot, start } => self.groups[slot] = start,
+                Frame::Loop { slot, iterations } => self.loops[slot] = iterations,
+                Frame::Atomic { slot, mark } => self.atomics[slot] = mark
+            }
+        }
+
+        None
+    }
+}
+
+/// Keeps the error that went the furthest, or the latest one
+fn furthest<'a>(error: Option<PipelineError<'a, char>>, other: PipelineError<'a, char>) -> PipelineError<'a, char> {
+    match error {
+        Some(error) if error.position().offset > other.position().offset => error,
+        _ => other
+    }
+}
+
+impl Class{
+    fn contains(&self, c: &char) -> bool {
+        let found = self.items.iter().any(|item| match item {
+            ClassItem::Range(start, end) => (start..=end).contains(&c),
+            ClassItem::Builtin { class, negated } => class.contains(c) != *negated
+        });
+
+        found != self.negated
+    }
+
+    fn expect<'i, 'a>(&'a self, mut p: MatchingPipeline<'i, char>) -> PipelineResult<'i, 'a, char> {
+        let Some((actual, width)) = p.current() else {
+            return Err(PipelineError::UnexpectedEos { position: p.position() });
+        };
+
+        if !self.contains(&actual) {
+            return Err(PipelineError::SymbolNotInClass { class: Cow::Borrowed(&self.name), actual: *actual, position: p.position() });
+        }
+
+        p.advance(width, 1);
+        Ok(p)
+    }
+
+    fn builtin(class: CharClass, negated: bool, name: String) -> Self {
+        Self { name, negated: false, items: vec![ClassItem::Builtin { class, negated }] }
+    }
+}
+
+struct Parser{
+    chars: Vec<char>,
+    pos: usize,
+    /// Number of groups numbered so far
+    groups: usize,
+    names: Vec<Rc<str>>
+}
+
+impl Parser{
+    fn parse(mut self) -> Result<Node, RegexError> {
+        let node = self.alternation()?;
+
+        match self.peek() {
+            Some(')') => Err(self.error(RegexErrorKind::UnopenedGroup, self.pos..self.pos + 1)),
+            _ => Ok(node)
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Consumes the current char if it is `c`
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+
+        found
+    }
+
+    fn error(&self, kind: RegexErrorKind, span: Range<usize>) -> RegexError {
+        RegexError { kind, span }
+    }
+
+    fn alternation(&mut self) -> Result<Node, RegexError> {
+        let mut branches = vec![self.concat()?];
+
+        while self.eat('|') {
+            branches.push(self.concat()?);
+        }
+
+        Ok(match branches.len() {
+            1 => branches.remove(0),
+            _ => Node::Alternation(branches)
+        })
+    }
+
+    fn concat(&mut self) -> Result<Node, RegexError> {
+        let mut nodes = vec![];
+
+        while !matches!(self.peek(), None | Some('|') | Some(')')) {
+            nodes.push(self.repeat()?);
+        }
+
+        Ok(match nodes.len() {
+            1 => nodes.remove(0),
+            _ => Node::Concat(nodes)
+        })
+    }
+
+    fn repeat(&mut self) -> Result<Node, RegexError> {
+        let mut node = self.atom()?;
+
+        if let Some((min, max)) = self.quantifier()? {
+            let mode = if self.eat('?') {
+                QuantifierMode::Lazy
+            }else if self.eat('+') {
+                QuantifierMode::Possessive
+            }else{
+                QuantifierMode::Greedy
+            };
+
+            node = Node::Repeat { node: Box::new(node), min, max, mode };
+        }
+
+        match self.peek() {
+            Some('?' | '*' | '+' | '{') if matches!(node, Node::Repeat { .. }) => {
+                Err(self.error(RegexErrorKind::NothingToRepeat, self.pos..self.pos + 1))
+            },
+            _ => Ok(node)
+        }
+    }
+
+    /// Parses the bounds of a quantifier, if any
+    fn quantifier(&mut self) -> Result<Option<(usize, usize)>, RegexError> {
+        let start = self.pos;
+
+        let bounds = match self.peek() {
+            Some('?') => (0, 1),
+            Some('*') => (0, usize::MAX),
+            Some('+') => (1, usize::MAX),
+            Some('{') => {
+                self.pos += 1;
+                return self.repetition(start).map(Some);
+            },
+            _ => return Ok(None)
+        };
+
+        self.pos += 1;
+        Ok(Some(bounds))
+    }
+
+    /// Parses `{m}`, `{m,}` or `{m,n}` from right after the brace at `start`
+    fn repetition(&mut self, start: usize) -> Result<(usize, usize), RegexError> {
+        let min = self.number();
+        let max = if self.eat(',') {
+            match self.peek() {
+                Some('}') => Some(usize::MAX),
+                _ => self.number()
+            }
+        }else{
+            min
+        };
+
+        if self.peek().is_none() {
+            return Err(self.error(RegexErrorKind::UnclosedRepetition, start..self.pos));
+        }
+
+        if !self.eat('}') {
+            return Err(self.error(RegexErrorKind::InvalidRepetition, start..self.pos + 1));
+        }
+
+        match (min, max) {
+            (Some(min), Some(max)) if min <= max => Ok((min, max)),
+            _ => Err(self.error(RegexErrorKind::InvalidRepetition, start..self.pos))
+        }
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        self.chars[start..self.pos].iter().collect::<String>().parse().ok()
+    }
+
+    fn atom(&mut self) -> Result<Node, RegexError> {
+        let start = self.pos;
+        let c = self.peek().expect("An atom is not empty");
+        self.pos += 1;
+
+        match c {
+            '(' => self.group(start),
+            '[' => self.class(start).map(Node::Class),
+            '.' => Ok(Node::Class(Class::builtin(ANY, false, ".".to_string()))),
+            '^' => Ok(Node::Start),
+            '$' => Ok(Node::End),
+            '\\' => match self.escape(start)? {
+                ClassItem::Range(c, _) => Ok(Node::Symbol(c)),
+                ClassItem::Builtin { class, negated } => {
+                    let name = if negated { self.chars[start..self.pos].iter().collect() } else { class.name().to_string() };
+                    Ok(Node::Class(Class::builtin(class, negated, name)))
+                }
+            },
+            '?' | '*' | '+' | '{' => Err(self.error(RegexErrorKind::NothingToRepeat, start..self.pos)),
+            c => Ok(Node::Symbol(c))
+        }
+    }
+
+    /// Parses a group from right after the parenthesis at `start`
+    fn group(&mut self, start: usize) -> Result<Node, RegexError> {
+        let (capturing, name) = if self.eat('?') {
+            if self.eat(':') {
+                (false, None)
+            }else if self.eat('<') || (self.eat('P') && self.eat('<')) {
+                (true, Some(self.group_name()?))
+            }else{
+                return Err(self.error(RegexErrorKind::UnsupportedGroup, start..self.pos + 1));
+            }
+        }else{
+            (true, None)
+        };
+
+        // Named groups are numbered as well, in the order they open
+        let mut names = vec![];
+        if capturing {
+            self.groups += 1;
+            names.push(Rc::from(self.groups.to_string()));
+            names.extend(name);
+        }
+
+        let node = self.alternation()?;
+
+        if !self.eat(')') {
+            return Err(self.error(RegexErrorKind::UnclosedGroup, start..start + 1));
+        }
+
+        Ok(Node::Group { names, node: Box::new(node) })
+    }
+
+    /// Parses a group name and its closing `>`
+    fn group_name(&mut self) -> Result<Rc<str>, RegexError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.pos += 1;
+        }
+
+        let name: String = self.chars[start..self.pos].iter().collect();
+        let valid = name.chars().next().is_some_and(|c| !c.is_ascii_digit());
+
+        if !valid || !self.eat('>') {
+            return Err(self.error(RegexErrorKind::InvalidGroupName, start..self.pos + 1));
+        }
+
+        if self.names.iter().any(|known| **known == *name) {
+            return Err(self.error(RegexErrorKind::DuplicateGroupName(name), start..self.pos - 1));
+        }
+
+        let name: Rc<str> = Rc::from(name);
+        self.names.push(name.clone());
+        Ok(name)
+    }
+
+    /// Parses a class from right after the bracket at `start`
+    fn class(&mut self, start: usize) -> Result<Class, RegexError> {
+        let negated = self.eat('^');
+        let mut items = vec![];
+
+        loop {
+            let item_start = self.pos;
+            let Some(c) = self.peek() else {
+                return Err(self.error(RegexErrorKind::UnclosedClass, start..start + 1));
+            };
+            self.pos += 1;
+
+            let item = match c {
+                ']' if !items.is_empty() => break,
+                '\\' => self.escape(item_start)?,
+                c => ClassItem::Range(c, c)
+            };
+
+            let item = match (item, self.chars.get(self.pos..self.pos + 2)) {
+                (ClassItem::Range(low, _), Some(&['-', end])) if end != ']' => {
+                    let end_start = self.pos + 1;
+                    self.pos += 2;
+
+                    let high = match end {
+                        '\\' => match self.escape(end_start)? {
+                            ClassItem::Range(high, _) => high,
+                            ClassItem::Builtin { .. } => return Err(self.error(
+                                RegexErrorKind::InvalidClassRange { start: low, end },
+                                item_start..self.pos
+                            ))
+                        },
+                        high => high
+                    };
+
+                    if low > high {
+                        return Err(self.error(RegexErrorKind::InvalidClassRange { start: low, end: high }, item_start..self.pos));
+                    }
+
+                    ClassItem::Range(low, high)
+                },
+                (item, _) => item
+            };
+
+            items.push(item);
+        }
+
+        Ok(Class { name: self.chars[start..self.pos].iter().collect(), negated, items })
+    }
+
+    /// Parses an escape sequence from right after the backslash at `start`
+    ///
+    /// A single char is returned as a range of one char
+    fn escape(&mut self, start: usize) -> Result<ClassItem, RegexError> {
+        let Some(c) = self.peek() else {
+            return Err(self.error(RegexErrorKind::TrailingBackslash, start..start + 1));
+        };
+        self.pos += 1;
+
+        let builtin = |class, negated| Ok(ClassItem::Builtin { class, negated });
+
+        match c {
+            'd' => builtin(classes::ASCII_DIGIT, false),
+            'D' => builtin(classes::ASCII_DIGIT, true),
+            'w' => builtin(classes::WORD, false),
+            'W' => builtin(classes::WORD, true),
+            's' => builtin(classes::WHITESPACE, false),
+            'S' => builtin(classes::WHITESPACE, true),
+            'n' => Ok(ClassItem::Range('\n', '\n')),
+            'r' => Ok(ClassItem::Range('\r', '\r')),
+            't' => Ok(ClassItem::Range('\t', '\t')),
+            c if c.is_ascii_punctuation() || c == ' ' => Ok(ClassItem::Range(c, c)),
+            c => Err(self.error(RegexErrorKind::UnknownEscape(c), start..self.pos))
+        }
+    }
+}
+
+impl Display for RegexErrorKind{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnclosedGroup => write!(f, "Unclosed group"),
+            Self::UnopenedGroup => write!(f, "Unopened group"),
+            Self::UnsupportedGroup => write!(f, "Unsupported group syntax"),
+            Self::InvalidGroupName => write!(f, "Invalid group name"),
+            Self::DuplicateGroupName(name) => write!(f, "Duplicate group name {name:?}"),
+            Self::UnclosedClass => write!(f, "Unclosed class"),
+            Self::InvalidClassRange { start, end } => write!(f, "Invalid class range {start:?}-{end:?}"),
+            Self::NothingToRepeat => write!(f, "Nothing to repeat"),
+            Self::UnclosedRepetition => write!(f, "Unclosed repetition"),
+            Self::InvalidRepetition => write!(f, "Invalid repetition"),
+            Self::UnknownEscape(c) => write!(f, "Unknown escape sequence \\{c}"),
+            Self::TrailingBackslash => write!(f, "Pattern ends with a backslash")
+        }
+    }
+}
+
+impl Display for RegexError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "offset {}: {}", self.span.start, self.kind)
+    }
+}
+
+impl Error for RegexError{}
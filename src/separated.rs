@@ -0,0 +1,94 @@
+use std::{cell::RefCell, ops::Range};
+
+use crate::{quantifiers::WithQuantifier, MatchError, MatchingPipeline, PipelineResult, Quantifier, Symbol};
+
+/// The items matched by [separated_by](MatchingPipeline::separated_by) and its variants
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Separated{
+    spans: Vec<Range<usize>>
+}
+
+impl Separated{
+    /// Number of items matched
+    pub fn count(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Offsets of the first symbol of each item and of the symbol right after it
+    pub fn spans(&self) -> &[Range<usize>] {
+        &self.spans
+    }
+
+    pub fn into_spans(self) -> Vec<Range<usize>> {
+        self.spans
+    }
+}
+
+/// Whether a list may, or must, end with a separator
+#[derive(Clone, Copy, PartialEq)]
+enum Trailing{
+    Forbidden,
+    Optional,
+    Required
+}
+
+impl<'i, 'a, S:Symbol+'a> MatchingPipeline<'i, S>{
+    /// Matches items separated by a separator, as many times as the quantifier allows
+    /// 
+    /// The quantifier counts items, not separators.
+    /// A separator is only consumed when an item follows it.
+    /// 
+    /// # Panics
+    /// 
+    /// If the minimum of the quantifier exceeds its maximum, see [Between](crate::Between)
+    pub fn separated_by<Q, F, G, E>(self, item: F, separator: G, quantifier: Q) -> Result<(Self, Separated), E>
+    where Q: Quantifier, Self: WithQuantifier<'i, 'a, Q, S>, F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, G: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>
+    {
+        self.separated(item, separator, quantifier, Trailing::Forbidden)
+    }
+
+    /// Same as [separated_by](Self::separated_by), with an optional separator after the last item
+    pub fn separated_by_trailing<Q, F, G, E>(self, item: F, separator: G, quantifier: Q) -> Result<(Self, Separated), E>
+    where Q: Quantifier, Self: WithQuantifier<'i, 'a, Q, S>, F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, G: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>
+    {
+        self.separated(item, separator, quantifier, Trailing::Optional)
+    }
+
+    /// Matches items each followed by a terminator, as many times as the quantifier allows
+    pub fn terminated_by<Q, F, G, E>(self, item: F, terminator: G, quantifier: Q) -> Result<(Self, Separated), E>
+    where Q: Quantifier, Self: WithQuantifier<'i, 'a, Q, S>, F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, G: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>
+    {
+        self.separated(item, terminator, quantifier, Trailing::Required)
+    }
+
+    fn separated<Q, F, G, E>(self, item: F, separator: G, quantifier: Q, trailing: Trailing) -> Result<(Self, Separated), E>
+    where Q: Quantifier, Self: WithQuantifier<'i, 'a, Q, S>, F: Fn(Self) -> PipelineResult<'i, 'a, S, E>, G: Fn(Self) -> PipelineResult<'i, 'a, S, E>, E: MatchError<'a, S>
+    {
+        let spans = RefCell::new(vec![]);
+
+        let pipeline = self.with_quantifier(quantifier, |mut p| -> PipelineResult<'i, 'a, S, E> {
+            if trailing != Trailing::Required && !spans.borrow().is_empty() {
+                p = separator(p)?;
+            }
+
+            let start = p.offset;
+            p = item(p)?;
+            let end = p.offset;
+
+            if trailing == Trailing::Required {
+                p = separator(p)?;
+            }
+
+            spans.borrow_mut().push(start..end);
+            Ok(p)
+        })?;
+
+        let spans = spans.into_inner();
+        let pipeline = match trailing {
+            Trailing::Optional if !spans.is_empty() => separator(pipeline.clone()).unwrap_or(pipeline),
+            _ => pipeline
+        };
+
+        Ok((pipeline, Separated { spans }))
+    }
+}
@@ -0,0 +1,108 @@
+use std::{borrow::Cow, ops::Range, rc::Rc};
+
+use crate::Symbol;
+
+/// Where a [MatchingPipeline](crate::MatchingPipeline) reads its symbols from
+///
+/// Positions are expressed in units of the source:
+/// symbols for slices, bytes for text.
+#[derive(Debug, Clone)]
+pub(crate) enum Source<'i, S>{
+    /// Symbols borrowed from the caller
+    Slice(&'i [S]),
+
+    /// Symbols owned by the pipeline, shared between its copies
+    Shared(Rc<[S]>),
+
+    /// UTF-8 text decoded on the fly, for [char] pipelines
+    Text(&'i str),
+
+    /// Case folded text, for [char] pipelines
+    /// 
+    /// It holds the original text, the folded symbols
+    /// and the byte offset in the text of the char each symbol was folded from
+    Folded(&'i str, Rc<[S]>, Rc<[usize]>)
+}
+
+impl<'i, S:Symbol> Source<'i, S>{
+    /// The length of the source in units
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Slice(symbols) => symbols.len(),
+            Self::Shared(symbols) | Self::Folded(_, symbols, _) => symbols.len(),
+            Self::Text(text) => text.len()
+        }
+    }
+
+    /// Returns the symbol at `pos` and its width in units
+    pub(crate) fn get(&self, pos: usize) -> Option<(Cow<'_, S>, usize)> {
+        match self {
+            Self::Slice(symbols) => symbols.get(pos).map(|s| (Cow::Borrowed(s), 1)),
+            Self::Shared(symbols) | Self::Folded(_, symbols, _) => symbols.get(pos).map(|s| (Cow::Borrowed(s), 1)),
+            Self::Text(text) => text.get(pos..)
+                .and_then(|rest| rest.chars().next())
+                .map(|c| (Cow::Owned(decode(c)), c.len_utf8()))
+        }
+    }
+
+    /// Returns the position right after the `n` symbols starting at `pos`, or the end of the source
+    pub(crate) fn end(&self, pos: usize, n: usize) -> usize {
+        match self {
+            Self::Text(text) => text[pos..].char_indices().nth(n).map_or(text.len(), |(i, _)| pos + i),
+            _ => pos.saturating_add(n).min(self.len())
+        }
+    }
+
+    /// Returns up to `n` symbols starting at `pos` and the position right after them
+    pub(crate) fn take(&self, pos: usize, n: usize) -> (Vec<S>, usize) {
+        let mut symbols = Vec::with_capacity(n);
+        let mut end = pos;
+
+        while symbols.len() < n {
+            let Some((symbol, width)) = self.get(end) else {
+                break;
+            };
+
+            symbols.push(symbol.into_owned());
+            end += width;
+        }
+
+        (symbols, end)
+    }
+
+    /// Returns the symbols in `range`, borrowing them whenever the source allows it
+    pub(crate) fn slice(&self, range: Range<usize>) -> Cow<'i, [S]> {
+        match self {
+            Self::Slice(symbols) => Cow::Borrowed(&symbols[range]),
+            Self::Shared(symbols) | Self::Folded(_, symbols, _) => Cow::Owned(symbols[range].to_vec()),
+            Self::Text(text) => Cow::Owned(text[range].chars().map(decode).collect())
+        }
+    }
+
+    /// Counts the symbols in `range`
+    pub(crate) fn count(&self, range: Range<usize>) -> usize {
+        match self {
+            Self::Text(text) => text[range].chars().count(),
+            _ => range.len()
+        }
+    }
+
+    /// Returns the text in `range` if the source is text
+    /// 
+    /// For folded text, this is the original text the symbols in `range` were folded from
+    pub(crate) fn text(&self, range: Range<usize>) -> Option<&'i str> {
+        match self {
+            Self::Text(text) => Some(&text[range]),
+            Self::Folded(text, _, origins) => {
+                let origin = |pos| origins.get(pos).copied().unwrap_or(text.len());
+                Some(&text[origin(range.start)..origin(range.end)])
+            },
+            _ => None
+        }
+    }
+}
+
+/// Converts a char read from a text source, which only [char] pipelines have
+fn decode<S:Symbol>(c: char) -> S {
+    S::from_char(c).expect("Only char pipelines read text")
+}
@@ -1,6 +1,26 @@
 use std::num::NonZeroUsize;
 
-use crate::{begin_match, quantifiers::WithQuantifier, AtLeast, AtMost, Exactly, MatchingPipeline, PipelineError, ZeroOrOne};
+use std::{borrow::Cow, cell::Cell, time::{Duration, Instant}};
+
+use crate::{begin_match, begin_match_case_folded, begin_match_nfc, begin_match_nfkc, classes, comparators, pattern, quantifiers::WithQuantifier, quantifiers::WithQuantifierThen, Alt, AtLeast, AtMost, Between, BigEndianDigester, BinaryDigester, Capture, Combine, Context, DecimalDigester, Diagnostic, DigestError, Exactly, FloatDigester, HexDigester, IntDigester, LengthPrefix, LineColumn, LittleEndianDigester, LookupDigester, MatchAgainst, MatchError, MatchingPipeline, OctalDigester, OwnedPipelineError, PipelineError, PipelineResult, Position, Quantifier, QuantifierMode, RadixDigester, Regex, RegexError, RegexErrorKind, Repeat, Separated, Seq, StatefulDigester, TryDigester, ZeroOrMore, ZeroOrOne};
+
+/// Observable state of a [MatchingPipeline]
+#[derive(Debug)]
+struct State{
+    matched: Vec<char>,
+    unmatched: Vec<char>,
+    reached_eos: bool,
+    offset: usize
+}
+
+impl PartialEq<State> for MatchingPipeline<'_, char>{
+    fn eq(&self, other: &State) -> bool {
+        *self.matched() == other.matched
+        && *self.remaining() == other.unmatched
+        && self.is_eos() == other.reached_eos
+        && self.offset == other.offset
+    }
+}
 
 #[test]
 fn should_match_all_symbols() -> Result<(), PipelineError<'static, char>>{
@@ -11,7 +31,7 @@ fn should_match_all_symbols() -> Result<(), PipelineError<'static, char>>{
         .expect_symbol(&'l')?
         .expect_symbol(&'o')?;
 
-    let expected = MatchingPipeline{
+    let expected = State{
         matched: vec!['h', 'e', 'l', 'l', 'o'],
         unmatched: vec![],
         reached_eos: true,
@@ -30,7 +50,7 @@ fn should_not_match_symbol() -> Result<(), PipelineError<'static, char>> {
         .expect_symbol(&'o')?
         .expect_symbol(&'o');
 
-    let expected = Err(PipelineError::WrongSymbol { expected: &'o', actual: 'u' });
+    let expected = Err(PipelineError::WrongSymbol { expected: Cow::Borrowed(&'o'), actual: 'u', position: Position::at(2) });
 
     assert_eq!(result, expected);
     Ok(())
@@ -42,7 +62,7 @@ fn should_not_reach_eos() -> Result<(), PipelineError<'static, char>>{
         .expect_symbol(&'F')?
         .expect_symbol(&'o')?;
 
-    let expected = MatchingPipeline{
+    let expected = State{
         matched: vec!['F', 'o'],
         unmatched: vec!['x', 'y'],
         reached_eos: false,
@@ -59,7 +79,7 @@ fn should_match_pattern() -> Result<(), PipelineError<'static, char>>{
     let result = begin_match("0x85ADG Header")
         .expect_pattern(&['0','x','8','5','A','D','G'])?;
 
-    let expected = MatchingPipeline{
+    let expected = State{
         matched: vec!['0','x','8','5','A','D','G'],
         unmatched: vec![' ','H','e','a','d','e','r'],
         reached_eos: false,
@@ -76,7 +96,7 @@ fn should_not_match_pattern() {
     let result = begin_match("0x85ADG Header")
         .expect_pattern(pattern);
 
-    let expected = Err(PipelineError::WrongPattern { expected: pattern, actual: vec!['0','x','8','5','A','D','G'] });
+    let expected = Err(PipelineError::WrongPattern { expected: Cow::Borrowed(pattern), actual: vec!['0','x','8','5','A','D','G'], position: Position::at(0) });
 
     assert_eq!(result, expected);
 }
@@ -87,7 +107,7 @@ fn pattern_is_too_big() {
     let result = begin_match("0x")
         .expect_pattern(pattern);
 
-    let expected = Err(PipelineError::WrongPattern { expected: pattern, actual: vec!['0', 'x'] });
+    let expected = Err(PipelineError::WrongPattern { expected: Cow::Borrowed(pattern), actual: vec!['0', 'x'], position: Position::at(0) });
 
     assert_eq!(result, expected);
 }
@@ -97,7 +117,7 @@ fn should_match_until_comma(){
     let result = begin_match("Foo,Bar ,baz")
         .match_until(&[','], true);
 
-    let expected = MatchingPipeline{
+    let expected = State{
         matched: vec!['F','o','o',','],
         unmatched: vec!['B','a','r',' ',',','b','a','z'],
         reached_eos: false,
@@ -114,7 +134,7 @@ fn should_skip() {
         .skip()
         .consume();
 
-    let expected = MatchingPipeline{
+    let expected = State{
         matched: vec!['F', 'x'],
         unmatched: vec![],
         reached_eos: true,
@@ -135,7 +155,7 @@ fn should_match_any() -> Result<(), PipelineError<'static, char>>{
         .skip()
         .expect_any_of(digits)?;
 
-    let expected = MatchingPipeline{
+    let expected = State{
         matched: vec!['1', '2', '3'],
         unmatched: vec![],
         reached_eos: true,
@@ -157,7 +177,7 @@ fn state_should_be_preserved_in_block() -> Result<(), PipelineError<'static, cha
             .expect_symbol(&'B')
         })?.expect_symbol(&'a')?;
 
-    let expected = MatchingPipeline{
+    let expected = State{
         matched: vec!['a', 'b', 'c', 'F', 'o', 'o', '1', 'B', 'a'],
         unmatched: vec!['r', '2'],
         reached_eos: false,
@@ -176,7 +196,7 @@ fn should_match_exactly_3() -> Result<(), PipelineError<'static, char>>{
         .expect_any_of(&['a', 'b', 'c'])
     })?;
 
-    let expected = MatchingPipeline {
+    let expected = State{
         matched: vec!['1','8','a', '1','8','b', '1','8','c'],
         unmatched: vec![],
         reached_eos: true,
@@ -195,7 +215,7 @@ fn quantifier_exactly_do_not_match() -> Result<(), PipelineError<'static, char>>
         p.expect_pattern(&['a', 'b', 'a'])
     });
 
-    let expected = Err(PipelineError::WrongPattern { expected: &['a', 'b', 'a'], actual: vec!['O'] });
+    let expected = Err(PipelineError::WrongPattern { expected: Cow::Borrowed(&['a', 'b', 'a']), actual: vec!['O'], position: Position::at(6) });
 
     assert_eq!(result, expected);
     Ok(())
@@ -213,7 +233,7 @@ fn quantifier_zero_or_one_with_trailing_expectation() -> Result<(), PipelineErro
     .with_quantifier(ZeroOrOne, |p| p.expect_symbol(&'b'))?
     .expect_symbol(&'c')?;
 
-    let expected1 = MatchingPipeline{
+    let expected1 = State{
         unmatched: vec![],
         matched: vec!['a', 'b', 'c'],
         reached_eos: true,
@@ -225,14 +245,14 @@ fn quantifier_zero_or_one_with_trailing_expectation() -> Result<(), PipelineErro
     .with_quantifier(ZeroOrOne, |p| p.expect_symbol(&'b'))?
     .expect_symbol(&'c');
 
-    let expected2 = Err(PipelineError::UnexpectedEos);
+    let expected2 = Err(PipelineError::UnexpectedEos { position: Position::at(2) });
 
     let result3 = begin_match(candidate3)
     .expect_symbol(&'a')?
     .with_quantifier(ZeroOrOne, |p| p.expect_symbol(&'b'))?
     .expect_symbol(&'c')?;
 
-    let expected3 = MatchingPipeline{
+    let expected3 = State{
         unmatched: vec![],
         matched: vec!['a', 'c'],
         reached_eos: true,
@@ -244,7 +264,7 @@ fn quantifier_zero_or_one_with_trailing_expectation() -> Result<(), PipelineErro
     .with_quantifier(ZeroOrOne, |p| p.expect_symbol(&'b'))?
     .expect_symbol(&'c');
 
-    let expected4 = Err(PipelineError::WrongSymbol { expected: &'c', actual: 'x' });
+    let expected4 = Err(PipelineError::WrongSymbol { expected: Cow::Borrowed(&'c'), actual: 'x', position: Position::at(1) });
 
     assert_eq!(result1, expected1);
     assert_eq!(result2, expected2);
@@ -268,7 +288,7 @@ fn quantifier_zero_or_one_without_trailing_expectation() -> Result<(), PipelineE
     .expect_symbol(&'a')?
     .with_quantifier(ZeroOrOne, |p| p.expect_symbol(&'b'))?;
 
-    let expected1 = MatchingPipeline{
+    let expected1 = State{
         unmatched: vec!['c'],
         matched: vec!['a', 'b'],
         reached_eos: false,
@@ -279,7 +299,7 @@ fn quantifier_zero_or_one_without_trailing_expectation() -> Result<(), PipelineE
     .expect_symbol(&'a')?
     .with_quantifier(ZeroOrOne, |p| p.expect_symbol(&'b'))?;
 
-    let expected2 = MatchingPipeline{
+    let expected2 = State{
         unmatched: vec![],
         matched: vec!['a', 'b'],
         reached_eos: true,
@@ -290,7 +310,7 @@ fn quantifier_zero_or_one_without_trailing_expectation() -> Result<(), PipelineE
     .expect_symbol(&'a')?
     .with_quantifier(ZeroOrOne, |p| p.expect_symbol(&'b'))?;
 
-    let expected3 = MatchingPipeline{
+    let expected3 = State{
         unmatched: vec!['c'],
         matched: vec!['a'],
         reached_eos: false,
@@ -301,7 +321,7 @@ fn quantifier_zero_or_one_without_trailing_expectation() -> Result<(), PipelineE
     .expect_symbol(&'a')?
     .with_quantifier(ZeroOrOne, |p| p.expect_symbol(&'b'))?;
 
-    let expected4 = MatchingPipeline{
+    let expected4 = State{
         unmatched: vec!['x', 'c'],
         matched: vec!['a'],
         reached_eos: false,
@@ -325,7 +345,7 @@ fn quantifier_at_least() -> Result<(), PipelineError<'static, char>> {
     .with_quantifier(AtLeast(3), |p| p.expect_symbol(&'b'))?
     .expect_symbol(&'c')?;
 
-    let expected1 = MatchingPipeline{
+    let expected1 = State{
         unmatched: vec![],
         matched: vec!['a', 'b', 'b', 'b', 'c'],
         reached_eos: true,
@@ -336,20 +356,20 @@ fn quantifier_at_least() -> Result<(), PipelineError<'static, char>> {
     .expect_symbol(&'a')?
     .with_quantifier(AtLeast(3), |p| p.expect_symbol(&'b'));
 
-    let expected2 = Err(PipelineError::UnexpectedEos);
+    let expected2 = Err(PipelineError::UnexpectedEos { position: Position::at(3) });
 
     let result3 = begin_match("abx")
     .expect_symbol(&'a')?
     .with_quantifier(AtLeast(3), |p| p.expect_symbol(&'b'));
 
-    let expected3 = Err(PipelineError::WrongSymbol { expected: &'b', actual: 'x' });
+    let expected3 = Err(PipelineError::WrongSymbol { expected: Cow::Borrowed(&'b'), actual: 'x', position: Position::at(2) });
 
     let result4 = begin_match("abbbc")
     .expect_symbol(&'a')?
     .with_quantifier(AtLeast(1), |p| p.expect_symbol(&'b'))?
     .expect_symbol(&'c')?;
 
-    let expected4 = MatchingPipeline{
+    let expected4 = State{
         unmatched: vec![],
         matched: vec!['a', 'b', 'b', 'b', 'c'],
         reached_eos: true,
@@ -361,7 +381,7 @@ fn quantifier_at_least() -> Result<(), PipelineError<'static, char>> {
     .with_quantifier(AtLeast(0), |p| p.expect_symbol(&'b'))?
     .expect_symbol(&'c')?;
 
-    let expected5 = MatchingPipeline{
+    let expected5 = State{
         unmatched: vec![],
         matched: vec!['a', 'b', 'b', 'c'],
         reached_eos: true,
@@ -373,7 +393,7 @@ fn quantifier_at_least() -> Result<(), PipelineError<'static, char>> {
     .with_quantifier(AtLeast(0), |p| p.expect_symbol(&'b'))?
     .expect_symbol(&'c')?;
 
-    let expected6 = MatchingPipeline{
+    let expected6 = State{
         unmatched: vec![],
         matched: vec!['a', 'c'],
         reached_eos: true,
@@ -397,7 +417,7 @@ fn quantifier_at_most() -> Result<(), PipelineError<'static, char>>{
     .with_quantifier(AtMost(NonZeroUsize::new(3).unwrap()), |p| p.expect_symbol(&'a'))?
     .expect_symbol(&'b')?;
 
-    let expected1 = MatchingPipeline{
+    let expected1 = State{
         unmatched: vec![],
         matched: vec!['a', 'a', 'b'],
         reached_eos: true,
@@ -407,7 +427,7 @@ fn quantifier_at_most() -> Result<(), PipelineError<'static, char>>{
     let result2 = begin_match("aaaax")
     .with_quantifier(AtMost(NonZeroUsize::new(3).unwrap()), |p| p.expect_symbol(&'a'))?;
 
-    let expected2 = MatchingPipeline{
+    let expected2 = State{
         unmatched: vec!['a', 'x'],
         matched: vec!['a', 'a', 'a'],
         reached_eos: false,
@@ -418,4 +438,1203 @@ fn quantifier_at_most() -> Result<(), PipelineError<'static, char>>{
     assert_eq!(result2, expected2);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+thread_local! {
+    static COMPARISONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Compares symbols like `==`, counting the comparisons made on the current thread
+fn counting_comparator(expected: &char, actual: &char) -> bool {
+    COMPARISONS.with(|count| count.set(count.get() + 1));
+    expected == actual
+}
+
+/// A pattern counting the calls to its callbacks
+type CountedPattern = for<'i> fn(MatchingPipeline<'i, char>, &Cell<usize>) -> MatchingPipeline<'i, char>;
+
+/// Runs `pattern` over an input of `len` symbols and returns the number of symbols it looked at
+fn count_steps(len: usize, pattern: CountedPattern) -> usize {
+    let input = "a".repeat(len) + "b";
+    let steps = Cell::new(0);
+    COMPARISONS.with(|count| count.set(0));
+
+    let result = pattern(begin_match(&input).with_comparator(counting_comparator), &steps);
+    assert_eq!(result.offset, len + 1);
+
+    steps.get() + COMPARISONS.with(Cell::get)
+}
+
+#[test]
+fn large_inputs_are_matched_in_linear_steps() {
+    let patterns: [CountedPattern; 3] = [
+        |p, _| p.match_until(&['b'], true),
+        |p, steps| p.match_while_true(|c| {
+            steps.set(steps.get() + 1);
+            *c == 'a'
+        }).consume(),
+        |p, steps| p.with_quantifier(ZeroOrMore, |p| {
+            steps.set(steps.get() + 1);
+            p.expect_symbol(&'a')
+        }).unwrap().expect_symbol(&'b').unwrap()
+    ];
+
+    for pattern in patterns {
+        let small = count_steps(10_000, pattern);
+        let large = count_steps(40_000, pattern);
+
+        assert!(small >= 10_000, "{small} steps for 10k symbols");
+        assert!(large <= 4 * small, "{small} steps for 10k symbols but {large} for 40k symbols");
+    }
+}
+
+/// Runs `pattern` over an input of `len` symbols and returns the best of a few timings
+fn time_matching<F>(len: usize, pattern: F) -> Duration
+where F: Fn(MatchingPipeline<'_, char>) -> MatchingPipeline<'_, char>
+{
+    let input = "a".repeat(len) + "b";
+
+    (0..3).map(|_| {
+        let start = Instant::now();
+        let result = pattern(begin_match(&input));
+        let elapsed = start.elapsed();
+
+        assert_eq!(result.offset, len + 1);
+        elapsed
+    }).min().unwrap()
+}
+
+/// Wall-clock timings depend on the machine, run with `cargo test --release -- --ignored`
+#[test]
+#[ignore]
+fn large_inputs_are_matched_in_linear_time() {
+    let patterns: [fn(MatchingPipeline<'_, char>) -> MatchingPipeline<'_, char>; 3] = [
+        |p| p.match_until(&['b'], true),
+        |p| p.match_while_true(|c| *c == 'a').consume(),
+        |p| p.with_quantifier(ZeroOrMore, |p| p.expect_symbol(&'a')).unwrap().expect_symbol(&'b').unwrap()
+    ];
+
+    for pattern in patterns {
+        let small = time_matching(250_000, pattern);
+        let large = time_matching(1_000_000, pattern);
+
+        // Linear matching takes ~4 times longer on the larger input, quadratic matching ~16 times
+        assert!(large < small * 10, "{small:?} for 250k symbols but {large:?} for 1M symbols");
+    }
+}
+
+#[test]
+fn matched_text_is_borrowed_from_the_input() -> Result<(), PipelineError<'static, char>> {
+    let input = String::from("héllo wörld");
+    let result = begin_match(&input)
+        .match_until(&[' '], false)
+        .terminate();
+
+    let matched = result.matched_str();
+    assert!(matches!(matched, Cow::Borrowed(_)));
+    assert_eq!(matched, "héllo");
+    assert_eq!(matched.as_ptr(), input.as_ptr());
+    assert_eq!(result.unmatched_str(), " wörld");
+    assert_eq!(result.offset(), 5);
+
+    let result = begin_match(&input)
+        .match_until(&[' '], true)
+        .expect_symbol(&'w')?
+        .expect_symbol(&'ö')?
+        .terminate();
+
+    assert_eq!(result.matched_str(), "héllo wö");
+    assert_eq!(result.unmatched_str(), "rld");
+    assert_eq!(*result.matched(), ['h', 'é', 'l', 'l', 'o', ' ', 'w', 'ö']);
+
+    Ok(())
+}
+
+#[test]
+fn matched_symbols_are_borrowed_from_the_input() -> Result<(), PipelineError<'static, char>> {
+    let input = vec!['a', 'b', 'c', 'd'];
+    let result = begin_match(&input)
+        .expect_symbol(&'a')?
+        .expect_symbol(&'b')?
+        .terminate();
+
+    assert!(matches!(result.matched(), Cow::Borrowed(['a', 'b'])));
+    assert!(matches!(result.unmatched(), Cow::Borrowed(['c', 'd'])));
+
+    Ok(())
+}
+
+#[test]
+fn skipped_symbols_are_left_out_of_the_matched_text() {
+    let result = begin_match("a,b")
+        .consume()
+        .skip()
+        .consume()
+        .terminate();
+
+    assert_eq!(result.matched_str(), "ab");
+    assert_eq!(result.matched_span_str(), None);
+}
+
+#[test]
+fn matched_span_is_a_slice_of_the_input() {
+    let input = String::from("héllo wörld");
+    let result = begin_match(&input)
+        .match_until(&[' '], false)
+        .terminate();
+
+    let span = result.matched_span_str().unwrap();
+    assert_eq!(span, "héllo");
+    assert_eq!(span.as_ptr(), input.as_ptr());
+
+    let symbols = vec!['a', 'b'];
+    assert_eq!(begin_match(&symbols).consume().terminate().matched_span_str(), None);
+}
+
+#[test]
+fn should_match_binary_fields() -> Result<(), PipelineError<'static, u8>> {
+    let packet: &[u8] = &[b'P', b'K', 0x01, 0x02, 0x78, 0x56, 0x34, 0x12, 0xFF];
+
+    let magic = begin_match(packet)
+        .expect_pattern(b"PK")?
+        .terminate();
+
+    let (pipeline, version) = begin_match(magic.unmatched()).expect_u16_be()?;
+    let (pipeline, size) = pipeline.expect_u32_le()?;
+    let (pipeline, flags) = pipeline.expect_u8()?;
+
+    assert_eq!(*magic.matched(), *b"PK");
+    assert_eq!(version, 0x0102);
+    assert_eq!(size, 0x12345678);
+    assert_eq!(flags, 0xFF);
+    assert!(pipeline.is_eos());
+
+    let (_, big) = begin_match(&packet[4..]).expect_u32_be()?;
+    assert_eq!(big, 0x78563412);
+    let (_, long) = begin_match(&packet[1..]).expect_u64_le()?;
+    assert_eq!(long, 0xFF12_3456_7802_014B);
+    assert_eq!(begin_match(&packet[1..]).expect_u64_be()?.1, 0x4B01_0278_5634_12FF);
+
+    let result = begin_match(&packet[8..]).expect_u16_le();
+    assert_eq!(result, Err(PipelineError::UnexpectedEos { position: Position::at(0) }));
+
+    Ok(())
+}
+
+#[test]
+fn should_match_length_prefixed_payload() -> Result<(), PipelineError<'static, u8>> {
+    let packet = vec![0x00, 0x03, b'a', b'b', b'c', 0x02, b'd'];
+
+    let (pipeline, payload) = begin_match(&packet)
+        .expect_length_prefixed(LengthPrefix::U16Be)?;
+
+    assert!(matches!(payload.matched(), Cow::Borrowed(b"abc")));
+    assert_eq!(*pipeline.clone().terminate().matched(), [0x00, 0x03, b'a', b'b', b'c']);
+
+    let result = pipeline.expect_length_prefixed(LengthPrefix::U8);
+    assert!(matches!(result, Err(PipelineError::UnexpectedEos { .. })));
+
+    let text = begin_match(b"abc".to_vec())
+        .expect_bytes(2)?
+        .terminate()
+        .digest::<BigEndianDigester<u32>>();
+    assert_eq!(text, 0x6162);
+
+    Ok(())
+}
+
+#[test]
+fn should_match_first_matching_alternative() -> Result<(), PipelineError<'static, char>> {
+    let keyword = |p: MatchingPipeline<'static, char>| p.one_of([
+        &|p| p.expect_pattern(&['l', 'e', 't']),
+        &|p| p.expect_pattern(&['l', 'o', 'o', 'p']),
+        &|p| p.expect_pattern(&['l', 'o'])
+    ]);
+
+    assert_eq!(*keyword(begin_match("let"))?.terminate().matched(), ['l', 'e', 't']);
+    assert_eq!(*keyword(begin_match("loop"))?.terminate().matched(), ['l', 'o', 'o', 'p']);
+    assert_eq!(*keyword(begin_match("lo"))?.terminate().matched(), ['l', 'o']);
+
+    let result = begin_match("b;")
+        .or(|p| p.expect_symbol(&'a'), |p| p.expect_symbol(&'b'))?
+        .expect_symbol(&';')?;
+
+    assert_eq!(result, State{
+        matched: vec!['b', ';'],
+        unmatched: vec![],
+        reached_eos: true,
+        offset: 2
+    });
+
+    Ok(())
+}
+
+#[test]
+fn failed_alternatives_merge_their_errors() {
+    let result = begin_match("c")
+        .or(
+            |p| p.expect_symbol(&'a'),
+            |p| p.or(|p| p.expect_symbol(&'b'), |p| p.expect_any_of(&['0', '1']))
+        );
+
+    let expected = Err(PipelineError::NoMatchingAlternative { position: Position::at(0), errors: vec![
+        PipelineError::WrongSymbol { expected: Cow::Borrowed(&'a'), actual: 'c', position: Position::at(0) },
+        PipelineError::WrongSymbol { expected: Cow::Borrowed(&'b'), actual: 'c', position: Position::at(0) },
+        PipelineError::SymbolNotMatchAnyOf { expected: Cow::Borrowed(&['0', '1']), actual: 'c', position: Position::at(0) }
+    ] });
+
+    assert_eq!(result, expected);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "offset 0: None of the alternatives matched: Expected 'a' but instead got 'c' or Expected 'b' but instead got 'c' or Expected one of ['0', '1'] but instead got 'c'"
+    );
+}
+
+#[test]
+fn errors_tell_line_and_column() -> Result<(), PipelineError<'static, char>> {
+    let result = begin_match("name: foo\nport= 80")
+        .track_lines('\n')
+        .match_until(&['\n'], true)
+        .match_while_true(|c| c.is_alphabetic())
+        .expect_symbol(&':');
+
+    let expected = Err(PipelineError::WrongSymbol {
+        expected: Cow::Borrowed(&':'),
+        actual: '=',
+        position: Position{ offset: 14, line_column: Some(LineColumn{ line: 2, column: 5 }) }
+    });
+
+    assert_eq!(result, expected);
+    assert_eq!(result.unwrap_err().to_string(), "line 2, column 5: Expected ':' but instead got '='");
+
+    let result = begin_match("a\nb\nc")
+        .expect_pattern(&['a', '\n', 'b'])?
+        .track_lines('\n')
+        .expect_symbol(&'\n')?
+        .terminate();
+
+    assert_eq!(result.position(), Position{ offset: 4, line_column: Some(LineColumn{ line: 3, column: 1 }) });
+
+    Ok(())
+}
+
+#[test]
+fn alternatives_keep_the_furthest_errors() {
+    let result = begin_match("abd").one_of([
+        &|p| p.expect_symbol(&'x'),
+        &|p| p.expect_symbol(&'a')?.expect_symbol(&'b')?.expect_symbol(&'c'),
+        &|p| p.expect_pattern(&['a', 'b'])?.expect_any_of(&['e', 'f'])
+    ]);
+
+    let expected = Err(PipelineError::NoMatchingAlternative { position: Position::at(2), errors: vec![
+        PipelineError::WrongSymbol { expected: Cow::Borrowed(&'c'), actual: 'd', position: Position::at(2) },
+        PipelineError::SymbolNotMatchAnyOf { expected: Cow::Borrowed(&['e', 'f']), actual: 'd', position: Position::at(2) }
+    ] });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn diagnostic_points_at_the_error() {
+    let config = "name: foo\nport= 80\n";
+    let error = begin_match(config)
+        .match_until(&['\n'], true)
+        .match_while_true(|c| c.is_alphabetic())
+        .expect_symbol(&':')
+        .unwrap_err();
+
+    let diagnostic = Diagnostic::new(config, &error)
+        .with_note("keys and values are separated by ':'")
+        .to_string();
+
+    assert_eq!(diagnostic, [
+        "error: Expected ':' but instead got '='",
+        " --> line 2, column 5",
+        "  |",
+        "2 | port= 80",
+        "  |     ^ expected ':'",
+        "  |",
+        "  = note: keys and values are separated by ':'",
+        ""
+    ].join("\n"));
+
+    let colored = Diagnostic::new(config, &error).colored(true).to_string();
+    assert!(colored.starts_with("\x1b[1;31merror\x1b[0m"));
+    assert!(colored.contains("\x1b[1;31m^ expected ':'\x1b[0m"));
+}
+
+#[test]
+fn diagnostic_underlines_the_whole_span() {
+    let input = "\tlet x = 1";
+    let error = begin_match(input)
+        .skip()
+        .one_of([
+            &|p| p.expect_pattern(&['v', 'a', 'r']),
+            &|p| p.expect_pattern(&['c', 'o', 'n', 's', 't'])
+        ])
+        .unwrap_err();
+
+    let diagnostic = Diagnostic::new(input, &error).to_string();
+
+    assert!(diagnostic.contains("\n  | \t^^^^^ expected \"var\" or \"const\"\n"), "{diagnostic}");
+}
+
+/// Builds its pattern at runtime, so its errors can't borrow it
+fn expect_keyword(input: &str, keyword: &str) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let pattern = keyword.chars().collect::<Vec<_>>();
+    let result = begin_match(input)
+        .expect_pattern(&pattern)
+        .map_err(PipelineError::into_owned)?;
+
+    Ok(result.terminate().offset())
+}
+
+#[test]
+fn owned_errors_outlive_the_pattern() {
+    assert_eq!(expect_keyword("let x", "let").unwrap(), 3);
+
+    let error = expect_keyword("var x", "let").unwrap_err();
+    assert_eq!(error.to_string(), "offset 0: Expected pattern ['l', 'e', 't'] but instead got ['v', 'a', 'r']");
+
+    let error: OwnedPipelineError<char> = {
+        let pattern = vec!['a', 'b'];
+        begin_match("ax").expect_pattern(&pattern).unwrap_err().into_owned()
+    };
+
+    let handle = std::thread::spawn(move || error.to_string());
+    assert_eq!(handle.join().unwrap(), "offset 0: Expected pattern ['a', 'b'] but instead got ['a', 'x']");
+}
+
+#[derive(Debug, PartialEq)]
+enum ConfigError<'a>{
+    Syntax(PipelineError<'a, char>),
+    PortOutOfRange(u32)
+}
+
+impl<'a> From<PipelineError<'a, char>> for ConfigError<'a>{
+    fn from(value: PipelineError<'a, char>) -> Self {
+        Self::Syntax(value)
+    }
+}
+
+impl<'a> MatchError<'a, char> for ConfigError<'a>{
+    fn into_pipeline_error(self) -> Result<PipelineError<'a, char>, Self> {
+        match self {
+            Self::Syntax(error) => Ok(error),
+            error => Err(error)
+        }
+    }
+}
+
+fn port(p: MatchingPipeline<'_, char>) -> Result<MatchingPipeline<'_, char>, ConfigError<'static>> {
+    let rest = p.clone().terminate().unmatched_str();
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+    let p = p.with_quantifier(AtLeast(1), |p| p.expect_predicate(|c| c.is_ascii_digit()))?;
+
+    let port = rest[..digits].parse::<u32>().unwrap();
+    if port > 65535 {
+        return Err(ConfigError::PortOutOfRange(port));
+    }
+
+    Ok(p)
+}
+
+#[test]
+fn blocks_fail_with_user_errors() {
+    let setting = |input| begin_match(input)
+        .expect_pattern(&['p', 'o', 'r', 't', '='])
+        .map_err(ConfigError::from)
+        .and_then(|p| p.block(port))
+        .map(|p| p.terminate().offset());
+
+    assert_eq!(setting("port=8080"), Ok(9));
+    assert_eq!(setting("port=80800"), Err(ConfigError::PortOutOfRange(80800)));
+    assert_eq!(setting("port=x"), Err(ConfigError::Syntax(PipelineError::SymbolNotMatchingPredicate { actual: 'x', position: Position::at(5) })));
+
+    // User errors stop the alternation instead of trying the next branch
+    let result = begin_match("99999").or(port, |p| Ok(p.match_until_eos()));
+    assert_eq!(result.map(|p| p.offset), Err(ConfigError::PortOutOfRange(99999)));
+}
+
+#[test]
+fn quantifiers_pass_user_errors_on() {
+    let out_of_range = Err(ConfigError::PortOutOfRange(99999));
+    let offset = |result: Result<MatchingPipeline<'_, char>, ConfigError<'static>>| result.map(|p| p.offset);
+
+    assert_eq!(offset(begin_match("99999").with_quantifier(ZeroOrMore, port)), out_of_range);
+    assert_eq!(offset(begin_match("99999").with_quantifier(ZeroOrOne, port)), out_of_range);
+    assert_eq!(offset(begin_match("80,99999").separated_by(port, |p| Ok(p.expect_symbol(&',')?), ZeroOrMore).map(|(p, _)| p)), out_of_range);
+
+    // Ordinary failures still end the repetition
+    assert_eq!(offset(begin_match("x").with_quantifier(ZeroOrMore, port)), Ok(0));
+
+    for mode in [QuantifierMode::Greedy, QuantifierMode::Lazy, QuantifierMode::Possessive] {
+        let eos = |p: MatchingPipeline<'static, char>| Ok(p.expect_eos()?);
+        let result = begin_match("99999").with_quantifier_then(ZeroOrMore, mode, port, eos);
+        assert_eq!(offset(result), out_of_range, "{mode:?}");
+
+        let space = |p: MatchingPipeline<'static, char>| Ok(p.expect_symbol(&' ')?);
+        let result = begin_match("  99999").with_quantifier_then(ZeroOrMore, mode, space, port);
+        assert_eq!(offset(result), out_of_range, "{mode:?}");
+    }
+}
+
+#[test]
+fn context_labels_stack_up() {
+    let header = |p: MatchingPipeline<'static, char>| p
+        .match_while_true(|c| c.is_alphabetic())
+        .expect_symbol(&':')
+        .context("while parsing key");
+
+    let result = begin_match("Host=localhost")
+        .block(header)
+        .context("while parsing header");
+
+    let error = result.unwrap_err();
+    assert_eq!(error.position(), Position::at(4));
+    assert_eq!(error.to_string(), "offset 4: while parsing header: while parsing key: Expected ':' but instead got '='");
+
+    let error = ConfigError::PortOutOfRange(99999);
+    assert_eq!(Err::<(), _>(error).context("while parsing port"), Err(ConfigError::PortOutOfRange(99999)));
+}
+
+#[test]
+fn should_capture_named_parts() -> Result<(), PipelineError<'static, char>> {
+    let input = "clé=välue;";
+    let result = begin_match(input)
+        .capture("key", |p| p.with_quantifier(AtLeast(1), |p| p.expect_predicate(|c| c.is_alphabetic())))?
+        .expect_symbol(&'=')?
+        .capture("value", |p| p.with_quantifier(AtLeast(1), |p| p.expect_predicate(|c| *c != ';')))?
+        .terminate();
+
+    let key = result.get("key").unwrap();
+    assert_eq!(key.span(), 0..3);
+    assert!(matches!(key.as_str(), Cow::Borrowed("clé")));
+
+    let value = result.get("value").unwrap();
+    assert_eq!(value.span(), 4..9);
+    assert_eq!(value.as_str(), "välue");
+    assert_eq!(*value.symbols(), ['v', 'ä', 'l', 'u', 'e']);
+
+    assert!(result.get("comment").is_none());
+    assert_eq!(result.captures().iter().map(Capture::name).collect::<Vec<_>>(), ["key", "value"]);
+
+    Ok(())
+}
+
+#[test]
+fn repeated_captures_are_collected() -> Result<(), PipelineError<'static, char>> {
+    let result = begin_match("1,22,333x")
+        .with_quantifier(AtLeast(1), |p| {
+            p.capture("item", |p| Ok(p.expect_predicate(|c| c.is_ascii_digit())?.match_while_true(|c| c.is_ascii_digit())))?
+            .with_quantifier(ZeroOrOne, |p| p.expect_symbol(&','))
+        })?
+        .terminate();
+
+    let items = result.get_all("item");
+    assert_eq!(items.iter().map(|c| c.as_str()).collect::<Vec<_>>(), ["1", "22", "333"]);
+    assert_eq!(items.iter().map(Capture::span).collect::<Vec<_>>(), [0..1, 2..4, 5..8]);
+    assert_eq!(result.get("item").unwrap().as_str(), "333");
+
+    // Captures of a failed branch are dropped along with it
+    let result = begin_match("ab")
+        .or(
+            |p| p.capture("a", |p| p.expect_symbol(&'a'))?.expect_symbol(&'c'),
+            |p| p.capture("ab", |p| p.expect_pattern(&['a', 'b']))
+        )?
+        .terminate();
+
+    assert_eq!(result.captures().iter().map(Capture::name).collect::<Vec<_>>(), ["ab"]);
+
+    Ok(())
+}
+
+#[test]
+fn digesting_malformed_input_is_reported() {
+    let digest = |input| begin_match(input).match_until_eos().terminate().try_digest::<IntDigester>();
+
+    assert_eq!(digest("-42"), Ok(-42));
+    assert_eq!(digest(""), Err(DigestError::Empty));
+    assert_eq!(digest("-"), Err(DigestError::InvalidDigit));
+    assert_eq!(digest("4x2"), Err(DigestError::InvalidDigit));
+    assert_eq!(digest("99999999999999999999"), Err(DigestError::Overflow));
+    assert_eq!(digest("-99999999999999999999"), Err(DigestError::Underflow));
+
+    let bytes = begin_match([1, 2, 3]).match_until_eos().terminate();
+    assert_eq!(bytes.try_digest::<BigEndianDigester<u16>>(), Err(DigestError::Overflow));
+
+    let digest_be = |input: &[u8]| begin_match(input.to_vec()).match_until_eos().terminate().digest::<BigEndianDigester<i16>>();
+    let digest_le = |input: &[u8]| begin_match(input.to_vec()).match_until_eos().terminate().digest::<LittleEndianDigester<i32>>();
+    assert_eq!(digest_be(&[0xFF]), -1);
+    assert_eq!(digest_be(&[0x7F]), 127);
+    assert_eq!(digest_le(&[0x00, 0x80]), -32768);
+    assert_eq!(digest_le(&[0x00, 0x7F]), 0x7F00);
+    let unsigned = begin_match(vec![0xFF]).match_until_eos().terminate().digest::<BigEndianDigester<u16>>();
+    assert_eq!(unsigned, 255);
+}
+
+/// Digests the whole `input` with `D`
+fn digest_all<D: TryDigester<char>>(input: &str) -> Result<D::Output, DigestError> {
+    begin_match(input).match_until_eos().terminate().try_digest::<D>()
+}
+
+#[test]
+fn should_digest_any_radix_and_width() {
+    assert_eq!(digest_all::<HexDigester<u32>>("0xFF_80_00"), Ok(0xFF8000));
+    assert_eq!(digest_all::<HexDigester<u32>>("ff8000"), Ok(0xFF8000));
+    assert_eq!(digest_all::<OctalDigester<u16>>("0o755"), Ok(0o755));
+    assert_eq!(digest_all::<BinaryDigester<u8>>("0b1010_0001"), Ok(0b1010_0001));
+    assert_eq!(digest_all::<DecimalDigester<u64>>("18446744073709551615"), Ok(u64::MAX));
+    assert_eq!(digest_all::<DecimalDigester<i128>>("-1_000_000"), Ok(-1_000_000));
+    assert_eq!(digest_all::<RadixDigester<i64, 36>>("+zz"), Ok(36 * 36 - 1));
+
+    // The prefix of another radix is made of digits or is invalid
+    assert_eq!(digest_all::<HexDigester<u16>>("0b1"), Ok(0xB1));
+    assert_eq!(digest_all::<OctalDigester<u16>>("0x1"), Err(DigestError::InvalidDigit));
+}
+
+#[test]
+fn should_report_malformed_numbers() {
+    assert_eq!(digest_all::<DecimalDigester<i8>>("-128"), Ok(i8::MIN));
+    assert_eq!(digest_all::<DecimalDigester<i8>>("127"), Ok(i8::MAX));
+    assert_eq!(digest_all::<DecimalDigester<i8>>("-129"), Err(DigestError::Underflow));
+    assert_eq!(digest_all::<DecimalDigester<i8>>("128"), Err(DigestError::Overflow));
+    assert_eq!(digest_all::<DecimalDigester<u8>>("256"), Err(DigestError::Overflow));
+    assert_eq!(digest_all::<DecimalDigester<u8>>("-1"), Err(DigestError::InvalidDigit));
+    assert_eq!(digest_all::<HexDigester<u8>>("0x"), Err(DigestError::Empty));
+    assert_eq!(digest_all::<DecimalDigester<u8>>("-"), Err(DigestError::Empty));
+    assert_eq!(digest_all::<DecimalDigester<u32>>("_1"), Err(DigestError::InvalidDigit));
+    assert_eq!(digest_all::<DecimalDigester<u32>>("1_"), Err(DigestError::InvalidDigit));
+    assert_eq!(digest_all::<DecimalDigester<u32>>("1a"), Err(DigestError::InvalidDigit));
+}
+
+#[test]
+fn should_match_float_literals() -> Result<(), PipelineError<'static, char>> {
+    let float = |input| -> Result<(String, f64), PipelineError<'static, char>> {
+        let result = begin_match(input).expect_float_literal()?.terminate();
+        Ok((result.matched_str().into_owned(), result.digest::<FloatDigester<f64>>()))
+    };
+
+    assert_eq!(float("2.5")?, ("2.5".to_string(), 2.5));
+    assert_eq!(float("-3.25e-10;")?, ("-3.25e-10".to_string(), -3.25e-10));
+    assert_eq!(float("+.5E3")?, ("+.5E3".to_string(), 500.0));
+    assert_eq!(float("1.")?, ("1.".to_string(), 1.0));
+    assert_eq!(float("42e")?, ("42".to_string(), 42.0));
+    assert_eq!(float("-inf")?, ("-inf".to_string(), f64::NEG_INFINITY));
+    assert_eq!(float("Infinity")?, ("Infinity".to_string(), f64::INFINITY));
+    assert!(float("NaN")?.1.is_nan());
+
+    assert!(begin_match(".").expect_float_literal().is_err());
+    assert!(begin_match("-e5").expect_float_literal().is_err());
+    assert!(begin_match("in").expect_float_literal().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn should_report_malformed_floats() {
+    assert_eq!(digest_all::<FloatDigester<f32>>("1e39"), Err(DigestError::Overflow));
+    assert_eq!(digest_all::<FloatDigester<f64>>("1e39"), Ok(1e39));
+    assert_eq!(digest_all::<FloatDigester<f32>>("-inf"), Ok(f32::NEG_INFINITY));
+    assert_eq!(digest_all::<FloatDigester<f64>>("1.2.3"), Err(DigestError::InvalidDigit));
+    assert_eq!(digest_all::<FloatDigester<f64>>(""), Err(DigestError::Empty));
+}
+
+/// Reads decimal numbers written with a configurable separator
+struct LocaleDigester{
+    separator: char
+}
+
+impl StatefulDigester<char> for LocaleDigester{
+    type Output = Result<f64, DigestError>;
+    fn digest(&self, symbols: &[char]) -> Self::Output {
+        let number = symbols.iter()
+            .map(|c| if *c == self.separator { '.' } else { *c })
+            .collect::<String>();
+
+        number.parse().map_err(|_| DigestError::InvalidDigit)
+    }
+}
+
+#[test]
+fn should_digest_with_configured_digesters() {
+    let number = |input| begin_match(input).match_until_eos().terminate();
+
+    assert_eq!(number("3,5").digest_with(&LocaleDigester{ separator: ',' }), Ok(3.5));
+    assert_eq!(number("3.5").digest_with(&LocaleDigester{ separator: '.' }), Ok(3.5));
+    assert_eq!(number("  42 ").digest_with(&|s: &[char]| s.iter().collect::<String>().trim().len()), 2);
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Level { Debug, Error }
+
+    let levels = LookupDigester::from_names([("debug", Level::Debug), ("error", Level::Error)]);
+    assert_eq!(number("error").digest_with(&levels), Ok(Level::Error));
+    assert!(number("warn").digest_with(&levels).is_err());
+}
+
+/// Matches as many 'a' as `quantifier` allows in each input
+/// and returns how many were matched, or the offset of the error
+fn quantify<Q:Quantifier>(quantifier: impl Fn() -> Q) -> Vec<Result<usize, usize>>
+where for<'i> MatchingPipeline<'i, char>: WithQuantifier<'i, 'static, Q, char>
+{
+    ["", "b", "ab", "aab", "aaab", "aaaab"].into_iter()
+        .map(|input| begin_match(input)
+            .with_quantifier(quantifier(), |p| p.expect_symbol(&'a'))
+            .map(|p| p.offset)
+            .map_err(|e| e.position().offset)
+        )
+        .collect()
+}
+
+#[test]
+fn quantifier_bounds_matrix() {
+    let three = || NonZeroUsize::new(3).unwrap();
+
+    assert_eq!(quantify(|| Between{ min: 1, max: 3 }), [Err(0), Err(0), Ok(1), Ok(2), Ok(3), Ok(3)]);
+    assert_eq!(quantify(|| 1..=3), [Err(0), Err(0), Ok(1), Ok(2), Ok(3), Ok(3)]);
+    assert_eq!(quantify(|| Between{ min: 0, max: 0 }), [Ok(0), Ok(0), Ok(0), Ok(0), Ok(0), Ok(0)]);
+    assert_eq!(quantify(|| 2..=2), [Err(0), Err(0), Err(1), Ok(2), Ok(2), Ok(2)]);
+    assert_eq!(quantify(|| Exactly(three())), [Err(0), Err(0), Err(1), Err(2), Ok(3), Ok(3)]);
+    assert_eq!(quantify(|| AtMost(three())), [Ok(0), Ok(0), Ok(1), Ok(2), Ok(3), Ok(3)]);
+    assert_eq!(quantify(|| ZeroOrOne), [Ok(0), Ok(0), Ok(1), Ok(1), Ok(1), Ok(1)]);
+    assert_eq!(quantify(|| AtLeast(2)), [Err(0), Err(0), Err(1), Ok(2), Ok(3), Ok(4)]);
+    assert_eq!(quantify(|| ZeroOrMore), [Ok(0), Ok(0), Ok(1), Ok(2), Ok(3), Ok(4)]);
+}
+
+#[test]
+#[should_panic(expected = "exceeds its maximum")]
+fn quantifier_min_cannot_exceed_max() {
+    let _ = begin_match("aaa").with_quantifier(Between{ min: 3, max: 2 }, |p| p.expect_symbol(&'a'));
+}
+
+#[test]
+#[should_panic(expected = "exceeds its maximum")]
+#[allow(clippy::reversed_empty_ranges)]
+fn quantifier_range_cannot_be_empty() {
+    let _ = begin_match("a,a").separated_by(|p| p.expect_symbol(&'a'), |p| p.expect_symbol(&','), 3..=2);
+}
+
+/// Matches a quoted string whose content is any run of characters
+fn quoted(input: &str, mode: QuantifierMode) -> Result<String, usize> {
+    begin_match(input)
+        .expect_symbol(&'"')
+        .and_then(|p| p.with_quantifier_then(ZeroOrMore, mode,
+            |p| p.expect_predicate(|_| true),
+            |p| p.expect_symbol(&'"')
+        ))
+        .map(|p| p.terminate().matched_str().into_owned())
+        .map_err(|e| e.position().offset)
+}
+
+#[test]
+fn quantifier_modes() {
+    assert_eq!(quoted(r#""ab"cd" rest"#, QuantifierMode::Lazy), Ok(r#""ab""#.to_string()));
+    assert_eq!(quoted(r#""ab"cd" rest"#, QuantifierMode::Greedy), Ok(r#""ab"cd""#.to_string()));
+    assert_eq!(quoted(r#""ab"cd" rest"#, QuantifierMode::Possessive), Err(12));
+
+    assert_eq!(quoted(r#""""#, QuantifierMode::Lazy), Ok(r#""""#.to_string()));
+    assert_eq!(quoted(r#""""#, QuantifierMode::Greedy), Ok(r#""""#.to_string()));
+    assert_eq!(quoted(r#""ab"#, QuantifierMode::Lazy), Err(3));
+    assert_eq!(quoted(r#""ab"#, QuantifierMode::Greedy), Err(3));
+}
+
+#[test]
+fn lazy_quantifier_respects_bounds() {
+    let digits = |input, mode| begin_match(input)
+        .with_quantifier_then(2..=3, mode, |p| p.expect_any_of(&['1', '2', '3', '4']), |p| p.expect_symbol(&'4'))
+        .map(|p| p.offset)
+        .map_err(|e: PipelineError<char>| e.position().offset);
+
+    assert_eq!(digits("1244", QuantifierMode::Lazy), Ok(3));
+    assert_eq!(digits("1244", QuantifierMode::Greedy), Ok(4));
+    assert_eq!(digits("1244", QuantifierMode::Possessive), Ok(4));
+    assert_eq!(digits("124", QuantifierMode::Greedy), Ok(3));
+    assert_eq!(digits("124", QuantifierMode::Possessive), Err(3));
+    assert_eq!(digits("144", QuantifierMode::Lazy), Ok(3));
+    assert_eq!(digits("14", QuantifierMode::Lazy), Err(2));
+    assert_eq!(digits("12314", QuantifierMode::Greedy), Err(3));
+}
+
+fn digit(p: MatchingPipeline<char>) -> PipelineResult<char> {
+    p.expect_predicate(|c| c.is_ascii_digit())
+}
+
+fn comma(p: MatchingPipeline<char>) -> PipelineResult<char> {
+    p.expect_symbol(&',')
+}
+
+#[test]
+fn separated_lists() {
+    let (p, items) = begin_match("1,2,3;").separated_by(digit, comma, ZeroOrMore).unwrap();
+    assert_eq!(items.count(), 3);
+    assert_eq!(items.spans(), [0..1, 2..3, 4..5]);
+    assert_eq!(p.offset, 5);
+
+    let (p, items) = begin_match("1,2,;").separated_by(digit, comma, ZeroOrMore).unwrap();
+    assert_eq!(items.count(), 2);
+    assert_eq!(p.offset, 3);
+
+    let (p, items) = begin_match(";").separated_by(digit, comma, ZeroOrMore).unwrap();
+    assert_eq!(items, Separated::default());
+    assert_eq!(p.offset, 0);
+
+    assert_eq!(
+        begin_match("1,;").separated_by(digit, comma, AtLeast(2)).unwrap_err().position(),
+        Position::at(2)
+    );
+
+    let (p, items) = begin_match("1,2,3,4").separated_by(digit, comma, 1..=2).unwrap();
+    assert_eq!(items.count(), 2);
+    assert_eq!(p.offset, 3);
+}
+
+#[test]
+fn separated_lists_with_trailing_separator() {
+    let (p, items) = begin_match("1,2,;").separated_by_trailing(digit, comma, ZeroOrMore).unwrap();
+    assert_eq!(items.count(), 2);
+    assert_eq!(p.offset, 4);
+
+    let (p, items) = begin_match("1,2;").separated_by_trailing(digit, comma, ZeroOrMore).unwrap();
+    assert_eq!(items.count(), 2);
+    assert_eq!(p.offset, 3);
+
+    let (p, _) = begin_match(",").separated_by_trailing(digit, comma, ZeroOrMore).unwrap();
+    assert_eq!(p.offset, 0);
+
+    let (p, items) = begin_match("1,2,3").terminated_by(digit, comma, ZeroOrMore).unwrap();
+    assert_eq!(items.spans(), [0..1, 2..3]);
+    assert_eq!(p.offset, 4);
+
+    assert_eq!(
+        begin_match("1,2").terminated_by(digit, comma, Exactly(NonZeroUsize::new(2).unwrap())).unwrap_err(),
+        PipelineError::UnexpectedEos { position: Position::at(3) }
+    );
+}
+
+#[test]
+fn quantifiers_stop_on_empty_iterations() {
+    let optional_a = |p: MatchingPipeline<'static, char>| p.with_quantifier(ZeroOrOne, |p| p.expect_symbol(&'a'));
+    let spaces = |p: MatchingPipeline<'static, char>| Ok::<_, PipelineError<char>>(p.match_while_true(|c| *c == ' '));
+
+    assert_eq!(begin_match("aab").with_quantifier(ZeroOrMore, optional_a).unwrap().offset, 2);
+    assert_eq!(begin_match("b").with_quantifier(AtLeast(3), optional_a).unwrap().offset, 0);
+    assert_eq!(begin_match("b").with_quantifier(AtLeast(3), spaces).unwrap().offset, 0);
+    assert_eq!(begin_match("  b").with_quantifier(ZeroOrMore, spaces).unwrap().offset, 2);
+    assert_eq!(
+        begin_match("ab").with_quantifier(ZeroOrMore, |p| p.with_quantifier(ZeroOrMore, optional_a)).unwrap().offset,
+        1
+    );
+
+    for mode in [QuantifierMode::Greedy, QuantifierMode::Lazy, QuantifierMode::Possessive] {
+        assert_eq!(
+            begin_match("aab").with_quantifier_then(AtLeast(5), mode, optional_a, |p| p.expect_symbol(&'b')).unwrap().offset,
+            3
+        );
+        assert_eq!(
+            begin_match("aac").with_quantifier_then(ZeroOrMore, mode, optional_a, |p| p.expect_symbol(&'b')).unwrap_err().position(),
+            Position::at(2)
+        );
+    }
+
+    let (p, items) = begin_match("b").separated_by(spaces, spaces, ZeroOrMore).unwrap();
+    assert_eq!((p.offset, items.count()), (0, 1));
+}
+
+fn identifier(p: MatchingPipeline<char>) -> PipelineResult<char> {
+    p.expect_predicate(|c| c.is_alphabetic()).map(|p| p.match_while_true(|c| c.is_alphanumeric()))
+}
+
+#[test]
+fn lookahead_does_not_consume() {
+    let minus = |input| begin_match(input)
+        .expect_symbol(&'-')
+        .and_then(|p| p.lookahead(digit))
+        .map(|p| p.offset);
+
+    assert_eq!(minus("-1"), Ok(1));
+    assert_eq!(minus("-x"), Err(PipelineError::SymbolNotMatchingPredicate { actual: 'x', position: Position::at(1) }));
+}
+
+#[test]
+fn negative_lookahead() {
+    let variable = |input| begin_match(input)
+        .block(identifier)
+        .and_then(|p| p.not(|p| p.match_while_true(|c| *c == ' ').expect_symbol(&'(')))
+        .map(|p| p.terminate().matched_str().into_owned());
+
+    assert_eq!(variable("foo + 1"), Ok("foo".to_string()));
+    assert_eq!(variable("foo"), Ok("foo".to_string()));
+
+    let error = variable("foo  (1)").unwrap_err();
+    assert_eq!(error, PipelineError::NotExpected { actual: vec![' ', ' ', '('], position: Position::at(3) });
+    assert_eq!(error.to_string(), "offset 3: Did not expect [' ', ' ', '(']");
+
+    let config = begin_match("x").not(|p| p.expect_symbol(&'x').context("forbidden"));
+    assert!(matches!(config, Err(PipelineError::NotExpected { .. })));
+    let user = begin_match("x").not(|_| Err(ConfigError::PortOutOfRange(0)));
+    assert_eq!(user, Err(ConfigError::PortOutOfRange(0)));
+}
+
+#[test]
+fn pipelines_can_be_inspected() {
+    let p = begin_match("héllo").expect_symbol(&'h').unwrap();
+
+    assert_eq!(p.offset(), 1);
+    assert_eq!(p.peek(), Some(Cow::Owned('é')));
+    assert_eq!(*p.peek_n(2), ['é', 'l']);
+    assert_eq!(*p.peek_n(10), ['é', 'l', 'l', 'o']);
+    assert_eq!(p.remaining_str(), "éllo");
+    assert_eq!(p.matched_str(), "h");
+    assert!(!p.is_eos());
+
+    let p = p.match_until_eos();
+    assert_eq!(p.peek(), None);
+    assert!(p.peek_n(3).is_empty());
+    assert!(p.is_eos());
+
+    let bytes: &[u8] = b"abc";
+    let p = begin_match(bytes).consume();
+    assert!(matches!(p.peek(), Some(Cow::Borrowed(b'b'))));
+    assert!(matches!(p.peek_n(5), Cow::Borrowed(b"bc")));
+    assert_eq!(*p.matched(), *b"a");
+    assert_eq!(*p.remaining(), *b"bc");
+}
+
+#[test]
+fn expect_eos_rejects_trailing_symbols() {
+    let number = begin_match("80")
+        .block(digit)
+        .and_then(|p| p.block(digit))
+        .and_then(MatchingPipeline::expect_eos);
+    assert_eq!(number.map(|p| p.offset()), Ok(2));
+
+    let error = begin_match("8x").block(digit).and_then(MatchingPipeline::expect_eos).unwrap_err();
+    assert_eq!(error, PipelineError::ExpectedEos { actual: 'x', position: Position::at(1) });
+    assert_eq!(error.to_string(), "offset 1: Expected the end of stream but instead got 'x'");
+    assert!(Diagnostic::new("8x", &error).to_string().contains("^ expected end of input"));
+}
+
+#[test]
+fn discarded_symbols_are_not_matched() {
+    let is_space = |c: &char| *c == ' ';
+    let number = begin_match("  42  ")
+        .skip_while(is_space)
+        .match_while_true(|c| c.is_ascii_digit())
+        .skip_while(is_space)
+        .expect_eos()
+        .unwrap()
+        .terminate();
+
+    assert_eq!(number.matched_str(), "42");
+    assert_eq!(number.digest::<IntDigester>(), 42);
+
+    let quoted = begin_match(r#""a,b" rest"#)
+        .skip_pattern(&['"'])
+        .map(|p| p.match_until(&['"'], false))
+        .and_then(|p| p.skip_pattern(&['"']))
+        .unwrap()
+        .terminate();
+    assert_eq!(quoted.matched_str(), "a,b");
+    assert_eq!(quoted.unmatched_str(), " rest");
+
+    let line = begin_match("key # comment\nnext")
+        .match_while_true(|c| c.is_alphabetic())
+        .skip_until(&['\n'], true)
+        .terminate();
+    assert_eq!(line.matched_str(), "key");
+    assert_eq!(line.unmatched_str(), "next");
+
+    let list = begin_match("1, 2, 3")
+        .separated_by(digit, |p| p.discard(|p| comma(p).map(|p| p.match_while_true(is_space))), ZeroOrMore)
+        .unwrap().0
+        .terminate();
+    assert_eq!(list.matched_str(), "123");
+
+    assert_eq!(
+        begin_match("ab").skip_pattern(&['a', 'c']).unwrap_err().position(),
+        Position::at(0)
+    );
+}
+
+#[test]
+fn symbols_in_range() {
+    let lowercase = |input| begin_match(input).with_quantifier(AtLeast(1), |p| p.expect_range('a'..='z'));
+
+    assert_eq!(lowercase("abcD").map(|p| p.offset()), Ok(3));
+    assert_eq!(
+        lowercase("Dabc").unwrap_err(),
+        PipelineError::SymbolNotInRange { expected: 'a'..='z', actual: 'D', position: Position::at(0) }
+    );
+    assert_eq!(lowercase("").unwrap_err(), PipelineError::UnexpectedEos { position: Position::at(0) });
+
+    let bytes: &[u8] = &[0x10, 0x7f, 0x80];
+    let ascii = begin_match(bytes).with_quantifier(ZeroOrMore, |p| p.expect_range(0x00..=0x7f)).unwrap();
+    assert_eq!(ascii.offset(), 2);
+}
+
+#[test]
+fn char_classes() {
+    let color = begin_match("#1aF0c9")
+        .expect_symbol(&'#')
+        .and_then(|p| p.with_quantifier(Exactly(NonZeroUsize::new(6).unwrap()), |p| p.expect_class(classes::HEX_DIGIT)))
+        .and_then(MatchingPipeline::expect_eos);
+    assert!(color.is_ok());
+
+    let error = begin_match("#1g")
+        .expect_symbol(&'#')
+        .and_then(|p| p.expect_class(classes::HEX_DIGIT)?.expect_class(classes::HEX_DIGIT))
+        .unwrap_err();
+    assert_eq!(error.to_string(), "offset 2: Expected hex digit but instead got 'g'");
+    assert!(Diagnostic::new("#1g", &error).to_string().contains("^ expected hex digit"));
+
+    let word = begin_match("Ⅻéa1 b").match_while_true(classes::ALPHANUMERIC.predicate()).terminate();
+    assert_eq!(word.matched_str(), "Ⅻéa1");
+
+    assert!(classes::NUMBER.contains(&'Ⅻ'));
+    assert!(!classes::ASCII_DIGIT.contains(&'Ⅻ'));
+    assert!(classes::LETTER.contains(&'é'));
+    assert!(!classes::ASCII_ALPHA.contains(&'é'));
+    assert!(classes::WHITESPACE.contains(&'\n'));
+    assert!(classes::ASCII_PUNCTUATION.contains(&'{'));
+    assert!(classes::ASCII_ALNUM.contains(&'z'));
+    assert!(begin_match("x").expect_predicate(classes::ASCII_ALPHA.predicate()).is_ok());
+
+    let error = begin_match("é").expect_class(classes::ASCII_ALPHA).unwrap_err();
+    assert_eq!(error.to_string(), "offset 0: Expected ASCII letter but instead got 'é'");
+    let error = begin_match("é").expect_class(classes::ASCII_ALNUM).unwrap_err();
+    assert_eq!(error.to_string(), "offset 0: Expected ASCII letter or digit but instead got 'é'");
+}
+
+#[test]
+fn comparators_apply_to_every_expectation() {
+    let header = begin_match("content-TYPE: text/html")
+        .with_comparator(comparators::ascii_case_insensitive)
+        .expect_pattern(&['C', 'o', 'n', 't', 'e', 'n', 't', '-', 'T', 'y', 'p', 'e'])
+        .and_then(|p| p.expect_symbol(&':'))
+        .map(|p| p.match_until(&['T', 'E', 'X', 'T'], true))
+        .and_then(|p| p.expect_any_of(&['/']))
+        .unwrap();
+    assert_eq!(header.offset(), 19);
+
+    let strict = begin_match("select").expect_pattern(&['S', 'E', 'L', 'E', 'C', 'T']);
+    assert!(strict.is_err());
+    assert!(begin_match("é").with_comparator(comparators::ascii_case_insensitive).expect_symbol(&'É').is_err());
+
+    let greek = begin_match("ΣΊΣΥΦΟΣ").with_comparator(comparators::simple_case_insensitive);
+    assert!(greek.expect_pattern(&['σ', 'ί', 'σ', 'υ', 'φ', 'ο', 'ς']).is_ok());
+    assert!(begin_match("\u{212A}").with_comparator(comparators::simple_case_insensitive).expect_symbol(&'k').is_ok());
+    assert!(begin_match("STRASSE").with_comparator(comparators::simple_case_insensitive).expect_pattern(&['s', 't', 'r', 'a', 'ß', 'e']).is_err());
+
+    let street = comparators::fold_case("STRASSE");
+    let result = begin_match_case_folded("Straße 12").expect_pattern(&street).unwrap().terminate();
+    assert_eq!(result.matched_str(), "Straße");
+    assert_eq!(result.unmatched_str(), " 12");
+    assert_eq!(result.offset(), 7);
+
+    let file = comparators::fold_case("file");
+    let result = begin_match_case_folded("ﬁLE.txt").capture("name", |p| p.expect_pattern(&file)).unwrap().terminate();
+    assert_eq!(result.get("name").unwrap().as_str(), "ﬁLE");
+    assert!(begin_match_case_folded("STRASE").expect_pattern(&street).is_err());
+
+    assert!(begin_match("\u{212B}").with_comparator(comparators::nfc).expect_symbol(&'\u{C5}').is_ok());
+    assert!(begin_match("²").with_comparator(comparators::nfc).expect_symbol(&'2').is_err());
+    assert!(begin_match("²").with_comparator(comparators::nfkc).expect_symbol(&'2').is_ok());
+
+    assert!(begin_match("e\u{301}").with_comparator(comparators::nfc).expect_symbol(&'é').is_err());
+    let composed = begin_match_nfc("cafe\u{301}!").expect_pattern(&['c', 'a', 'f', 'é']).unwrap().terminate();
+    assert_eq!(composed.matched_str(), "café");
+    assert_eq!(composed.unmatched_str(), "!");
+    assert!(begin_match_nfkc("x\u{B2}").expect_pattern(&['x', '2']).is_ok());
+
+    let bytes: &[u8] = b"GET /";
+    let method = begin_match(bytes).with_comparator(u8::eq_ignore_ascii_case).expect_pattern(b"get");
+    assert_eq!(method.map(|p| p.offset()), Ok(3));
+}
+
+#[test]
+fn regex_matches_and_captures() {
+    let setting = Regex::new(r#"(?<key>[a-z_]+)\s*=\s*(?<value>\d{1,5}|"[^"]*")$"#).unwrap();
+    let result = setting.match_str("max_port = 8080").unwrap();
+    assert_eq!(result.get("key").unwrap().as_str(), "max_port");
+    assert_eq!(result.get("value").unwrap().as_str(), "8080");
+
+    let result = setting.match_str(r#"name="a b""#).unwrap();
+    assert_eq!(result.get("value").unwrap().as_str(), r#""a b""#);
+
+    let error = setting.match_str("port = 123456").unwrap_err();
+    assert_eq!(error, PipelineError::ExpectedEos { actual: '6', position: Position::at(12) });
+    let error = setting.match_str("Port = 1").unwrap_err();
+    assert_eq!(error.to_string(), "offset 0: Expected [a-z_] but instead got 'P'");
+}
+
+#[test]
+fn regex_backtracks() {
+    let regex = |pattern| Regex::new(pattern).unwrap();
+    let matched = |pattern, input| regex(pattern).match_str(input).map(|p| p.matched_str().into_owned()).ok();
+
+    assert_eq!(matched("a.*b", "axbxbx"), Some("axbxb".to_string()));
+    assert_eq!(matched("a.*?b", "axbxbx"), Some("axb".to_string()));
+    assert_eq!(matched("a.*+b", "axbxbx"), None);
+    assert_eq!(matched("(?:a|b)*+b", "ab"), None);
+    assert_eq!(matched("(?:a|ab){2}+", "abab"), Some("aba".to_string()));
+    assert_eq!(matched("(ab|a)bc", "abc"), Some("abc".to_string()));
+    assert_eq!(matched("(a|ab)(c|bcd)", "abcd"), Some("abcd".to_string()));
+    assert_eq!(matched("(?:ab)+c", "ababc"), Some("ababc".to_string()));
+    assert_eq!(matched("(?:a|ab)*c", "abac"), Some("abac".to_string()));
+    assert_eq!(matched("(a*)*b", "aab"), Some("aab".to_string()));
+    assert_eq!(matched("(a?){3}b", "ab"), Some("ab".to_string()));
+    assert_eq!(matched(r"x{2,}y", "xxxy"), Some("xxxy".to_string()));
+    assert_eq!(matched(r"x{2,3}", "xxxx"), Some("xxx".to_string()));
+    assert_eq!(matched(r"x{2}", "x"), None);
+    assert_eq!(matched(r"^\W\S\D$", "- a"), None);
+    assert_eq!(matched(r"^\W\S\D$", "-aa"), Some("-aa".to_string()));
+    assert_eq!(matched(r"[\d.-]+", "-1.5e"), Some("-1.5".to_string()));
+    assert_eq!(matched(r"[]a]+", "]a]b"), Some("]a]".to_string()));
+    assert_eq!(matched(r"\(\)\.", "()."), Some("().".to_string()));
+    assert_eq!(matched("", "abc"), Some(String::new()));
+
+    let groups = regex(r"(\w)(?:-(\w))*").match_str("a-b-c").unwrap();
+    assert_eq!(groups.get("1").unwrap().as_str(), "a");
+    assert_eq!(groups.get_all("2").iter().map(|c| c.as_str()).collect::<Vec<_>>(), ["b", "c"]);
+
+    let groups = regex("(?<a>x)(y)((?<c>z))").match_str("xyz").unwrap();
+    assert_eq!(groups.get("a").unwrap().as_str(), "x");
+    assert_eq!(groups.get("1").unwrap().as_str(), "x");
+    assert_eq!(groups.get("2").unwrap().as_str(), "y");
+    assert_eq!(groups.get("3").unwrap().as_str(), "z");
+    assert_eq!(groups.get("4").unwrap().as_str(), "z");
+    assert_eq!(groups.get("c").unwrap().as_str(), "z");
+
+    let keyword = begin_match("SELECT *")
+        .with_comparator(comparators::ascii_case_insensitive)
+        .block(|p| regex("select ").run(p).map_err(PipelineError::into_owned));
+    assert_eq!(keyword.map(|p| p.offset()), Ok(7));
+}
+
+#[test]
+fn regex_repeats_groups_over_long_inputs() {
+    let input: String = "ab".repeat(100_000);
+    let result = Regex::new("(?:a|b)+").unwrap().match_str(&input).unwrap();
+    assert_eq!(result.offset(), 200_000);
+
+    let input = input + "c";
+    let result = Regex::new("(?:ab)*?c").unwrap().match_str(&input).unwrap();
+    assert_eq!(result.offset(), 200_001);
+
+    let result = Regex::new("(?<pair>ab)*").unwrap().match_str(&input).unwrap();
+    assert_eq!(result.get_all("pair").len(), 100_000);
+    assert_eq!(result.unmatched_str(), "c");
+
+    let result = Regex::new("(){1000000}a").unwrap().match_str("a").unwrap();
+    assert_eq!(result.offset(), 1);
+}
+
+#[test]
+fn regex_syntax_errors_point_into_the_pattern() {
+    let error = |pattern| Regex::new(pattern).unwrap_err();
+
+    assert_eq!(error("(ab"), RegexError { kind: RegexErrorKind::UnclosedGroup, span: 0..1 });
+    assert_eq!(error("ab)"), RegexError { kind: RegexErrorKind::UnopenedGroup, span: 2..3 });
+    assert_eq!(error("a(?=b)"), RegexError { kind: RegexErrorKind::UnsupportedGroup, span: 1..4 });
+    assert_eq!(error("(?<1x>a)"), RegexError { kind: RegexErrorKind::InvalidGroupName, span: 3..6 });
+    assert_eq!(error("(?<x>a)(?<x>b)"), RegexError { kind: RegexErrorKind::DuplicateGroupName("x".to_string()), span: 10..11 });
+    assert_eq!(error("[a-z"), RegexError { kind: RegexErrorKind::UnclosedClass, span: 0..1 });
+    assert_eq!(error("[z-a]"), RegexError { kind: RegexErrorKind::InvalidClassRange { start: 'z', end: 'a' }, span: 1..4 });
+    assert_eq!(error("*a"), RegexError { kind: RegexErrorKind::NothingToRepeat, span: 0..1 });
+    assert_eq!(error("a**"), RegexError { kind: RegexErrorKind::NothingToRepeat, span: 2..3 });
+    assert_eq!(error("a|?"), RegexError { kind: RegexErrorKind::NothingToRepeat, span: 2..3 });
+    assert_eq!(error("a{2,"), RegexError { kind: RegexErrorKind::UnclosedRepetition, span: 1..4 });
+    assert_eq!(error("a{3,1}b"), RegexError { kind: RegexErrorKind::InvalidRepetition, span: 1..6 });
+    assert_eq!(error("a{x}"), RegexError { kind: RegexErrorKind::InvalidRepetition, span: 1..3 });
+    assert_eq!(error(r"\q"), RegexError { kind: RegexErrorKind::UnknownEscape('q'), span: 0..2 });
+    assert_eq!(error(r"ab\"), RegexError { kind: RegexErrorKind::TrailingBackslash, span: 2..3 });
+
+    let pattern = r"key=[0-9]{3,1}";
+    let diagnostic = Diagnostic::for_regex(pattern, &error(pattern)).to_string();
+    assert_eq!(diagnostic, [
+        "error: Invalid repetition",
+        " --> line 1, column 10",
+        "  |",
+        "1 | key=[0-9]{3,1}",
+        "  |          ^^^^^",
+        ""
+    ].join("\n"));
+    assert_eq!(error(pattern).to_string(), "offset 9: Invalid repetition");
+}
+
+/// A grammar stored as values
+struct Literals<A, B>{
+    hex: A,
+    number: B
+}
+
+#[test]
+fn patterns_are_values() {
+    let hex = "0x".then(Repeat { quantifier: AtLeast(1), pattern: classes::HEX_DIGIT });
+    let number = classes::ASCII_DIGIT.quantified(AtLeast(1)).then('.'.then(classes::ASCII_DIGIT.quantified(ZeroOrMore)).quantified(ZeroOrOne));
+    let literals = Literals { hex, number };
+    let literal = literals.hex | literals.number;
+
+    let matched = |input| begin_match(input).expect(literal).map(|p| p.terminate().matched_str().into_owned());
+    assert_eq!(matched("0x1F;"), Ok("0x1F".to_string()));
+    assert_eq!(matched("12.5;"), Ok("12.5".to_string()));
+    assert_eq!(matched("7"), Ok("7".to_string()));
+
+    assert_eq!(matched("0xg"), Ok("0".to_string()));
+
+    let error = matched("x").unwrap_err();
+    assert_eq!(error.to_string(), "offset 0: None of the alternatives matched: Expected pattern ['0', 'x'] but instead got ['x'] or Expected digit but instead got 'x'");
+
+    let keyword = Alt("let", "const") + Seq(' ', identifier);
+    assert!(begin_match("const x").expect(keyword).is_ok());
+    assert!(begin_match("var x").expect(keyword).is_err());
+
+    let regex = Regex::new(r"\d+").unwrap();
+    let sum = Seq(&regex, Seq('+', &regex));
+    assert_eq!(begin_match("1+22").expect(sum).map(|p| p.offset()), Ok(4));
+
+    let magic: &[u8] = &[0x7f, b'E', b'L', b'F'];
+    let header = Seq(magic, 2u8.or(1u8));
+    assert_eq!(begin_match(b"\x7fELF\x01").expect(header).map(|p| p.offset()), Ok(5));
+    assert_eq!(begin_match(b"\x7fELF\x01").expect(&[0x7f, b'E']).map(|p| p.offset()), Ok(2));
+}
+
+#[test]
+fn match_against_accepts_any_pattern() {
+    let version = Seq("v", Repeat { quantifier: AtLeast(1), pattern: classes::ASCII_DIGIT });
+
+    assert_eq!("v12".match_against(version).map(|p| p.matched_str().into_owned()), Some("v12".to_string()));
+    assert!("x12".match_against(version).is_none());
+    assert_eq!("key: value".match_against(identifier).map(|p| p.offset()), Some(3));
+
+    let config = begin_match("x").expect(port);
+    assert!(matches!(config, Err(ConfigError::Syntax(_))));
+}
+
+#[test]
+fn pattern_macro() {
+    let hex = pattern!("0x" [hex]+);
+    let list = pattern!(item @ (hex | [digit]+) ([ws]* ',' [ws]* item @ (hex | [digit]+))* [ws]* ';');
+
+    let result = begin_match("0x1F, 42 ,7;").expect(list).unwrap().terminate();
+    assert_eq!(result.get_all("item").iter().map(|c| c.as_str()).collect::<Vec<_>>(), ["0x1F", "42", "7"]);
+    assert!(begin_match("0x1F, ;").expect(list).is_err());
+
+    let identifier = pattern!((['a'..='z'] | '_') (['a'..='z'] | ['0'..='9'] | '_'){0, 15});
+    let matched = |input| begin_match(input).expect(identifier.clone()).map(|p| p.offset()).ok();
+    assert_eq!(matched("snake_case_2 = 1"), Some(12));
+    assert_eq!(matched("a_very_long_identifier"), Some(16));
+    assert_eq!(matched("2x"), None);
+
+    let date = pattern!(year @ [digit]{4} '-' month @ [digit]{2} ('-' day @ [digit]{2,})?);
+    let result = begin_match("2024-05").expect(date).unwrap().terminate();
+    assert_eq!(result.get("month").unwrap().as_str(), "05");
+    assert!(result.get("day").is_none());
+
+    let keyword = pattern!("let" | "const" | "var");
+    assert_eq!(begin_match("var x").expect(keyword).map(|p| p.offset()), Ok(3));
+
+    let elf = pattern!(b"\x7fELF" (b'\x01' | b'\x02'));
+    assert!(begin_match(b"\x7fELF\x02").expect(elf).is_ok());
+}